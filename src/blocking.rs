@@ -0,0 +1,329 @@
+//! Blocking wrappers of the DBus objects, for applications that do not use an
+//! async runtime.
+//!
+//! Each type wraps its async counterpart and blocks on its methods, following
+//! the pattern of [`zbus::blocking`].
+
+use std::collections::HashMap;
+
+use zbus::{block_on, zvariant::OwnedValue, Result};
+
+use crate::{device, sensor, Owner, Scope};
+
+macro_rules! blocking_getters {
+    ($($(#[$attr:meta])* $name:ident -> $ty:ty;)*) => {
+        $(
+            $(#[$attr])*
+            pub fn $name(&self) -> Result<$ty> {
+                block_on(self.0.$name())
+            }
+        )*
+    };
+}
+
+/// A blocking wrapper of the `org.freedesktop.ColorManager` DBus interface.
+#[derive(Debug)]
+pub struct ColorManager<'a>(crate::ColorManager<'a>);
+
+impl<'a> ColorManager<'a> {
+    /// Creates a new instance of ColorManager
+    pub fn new() -> Result<ColorManager<'a>> {
+        block_on(crate::ColorManager::new()).map(Self)
+    }
+
+    /// Creates a new instance of ColorManager using a given connection, the
+    /// connection has to be a system connection.
+    pub fn from_connection(connection: &zbus::blocking::Connection) -> Result<ColorManager<'a>> {
+        block_on(crate::ColorManager::from_connection(connection.inner())).map(Self)
+    }
+
+    /// Get a reference to the underlying async ColorManager.
+    pub fn inner(&self) -> &crate::ColorManager<'a> {
+        &self.0
+    }
+
+    #[doc(alias = "GetDevices")]
+    /// Gets a list of all the devices which have assigned color profiles.
+    pub fn devices(&self) -> Result<Vec<Device<'static>>> {
+        let devices = block_on(self.0.devices())?;
+
+        Ok(devices.into_iter().map(Device).collect())
+    }
+
+    #[doc(alias = "GetDevicesByKind")]
+    /// Gets a list of all the devices of a specified kind.
+    pub fn devices_by_kind(&self, kind: &str) -> Result<Vec<Device<'_>>> {
+        let devices = block_on(self.0.devices_by_kind(kind))?;
+
+        Ok(devices.into_iter().map(Device).collect())
+    }
+
+    #[doc(alias = "FindDeviceById")]
+    /// Gets a device for the device ID.
+    pub fn find_device_by_id(&self, device_id: &str) -> Result<Device<'_>> {
+        block_on(self.0.find_device_by_id(device_id)).map(Device)
+    }
+
+    #[doc(alias = "FindProfileById")]
+    /// Gets a profile for the profile ID.
+    pub fn find_profile_by_id(&self, profile_id: &str) -> Result<Profile<'_>> {
+        block_on(self.0.find_profile_by_id(profile_id)).map(Profile)
+    }
+
+    #[doc(alias = "FindSensorById")]
+    /// Gets a sensor for the sensor ID.
+    pub fn find_sensor_by_id(&self, sensor_id: &str) -> Result<Sensor<'_>> {
+        block_on(self.0.find_sensor_by_id(sensor_id)).map(Sensor)
+    }
+
+    #[doc(alias = "GetProfiles")]
+    /// Gets a list of all the profiles recognised by the system.
+    pub fn profiles(&self) -> Result<Vec<Profile<'static>>> {
+        let profiles = block_on(self.0.profiles())?;
+
+        Ok(profiles.into_iter().map(Profile).collect())
+    }
+
+    #[doc(alias = "GetProfilesByKind")]
+    /// Gets a list of all the profiles of a specified type.
+    pub fn profiles_by_kind(&self, kind: &str) -> Result<Vec<Profile<'_>>> {
+        let profiles = block_on(self.0.profiles_by_kind(kind))?;
+
+        Ok(profiles.into_iter().map(Profile).collect())
+    }
+
+    #[doc(alias = "GetSensors")]
+    /// Gets a list of all the sensors recognised by the system.
+    pub fn sensors(&self) -> Result<Vec<Sensor<'_>>> {
+        let sensors = block_on(self.0.sensors())?;
+
+        Ok(sensors.into_iter().map(Sensor).collect())
+    }
+
+    blocking_getters! {
+        #[doc(alias = "DaemonVersion")]
+        /// The daemon version.
+        daemon_version -> String;
+        #[doc(alias = "SystemVendor")]
+        /// The system vendor.
+        system_vendor -> Option<String>;
+        #[doc(alias = "SystemModel")]
+        /// The system model.
+        system_model -> Option<String>;
+    }
+}
+
+/// A blocking wrapper of the `org.freedesktop.ColorManager.Device` DBus
+/// object.
+#[derive(Debug, Clone)]
+pub struct Device<'a>(crate::Device<'a>);
+
+impl<'a> Device<'a> {
+    /// Get a reference to the underlying async Device.
+    pub fn inner(&self) -> &crate::Device<'a> {
+        &self.0
+    }
+
+    /// Drops the cached property values, see [`crate::Device::refresh`].
+    pub fn refresh(&self) -> Result<()> {
+        block_on(self.0.refresh())
+    }
+
+    #[doc(alias = "SetEnabled")]
+    /// Sets the device enable state.
+    pub fn set_enabled(&self, enabled: bool) -> Result<()> {
+        block_on(self.0.set_enabled(enabled))
+    }
+
+    #[doc(alias = "Profiles")]
+    /// The profiles associated with this device.
+    pub fn profiles(&self) -> Result<Vec<Profile<'static>>> {
+        let profiles = block_on(self.0.profiles())?;
+
+        Ok(profiles.into_iter().map(Profile).collect())
+    }
+
+    blocking_getters! {
+        #[doc(alias = "Created")]
+        /// The date the device was created.
+        created -> u64;
+        #[doc(alias = "Modified")]
+        /// The date the device was modified.
+        modified -> u64;
+        #[doc(alias = "Model")]
+        /// The device model string.
+        model -> String;
+        #[doc(alias = "Serial")]
+        /// The device serial string.
+        serial -> String;
+        #[doc(alias = "Vendor")]
+        /// The device vendor string.
+        vendor -> String;
+        #[doc(alias = "Colorspace")]
+        /// The device colorspace string.
+        colorspace -> String;
+        #[doc(alias = "Kind")]
+        /// The device kind.
+        kind -> device::Kind;
+        #[doc(alias = "DeviceId")]
+        /// The device id string.
+        device_id -> String;
+        #[doc(alias = "Mode")]
+        /// The mode of the device.
+        mode -> device::Mode;
+        #[doc(alias = "Format")]
+        /// The qualifier format for the device.
+        format -> String;
+        #[doc(alias = "Scope")]
+        /// The scope of the device.
+        scope -> Scope;
+        #[doc(alias = "Owner")]
+        /// The user ID of the account that created the device.
+        owner -> Owner;
+        #[doc(alias = "Enabled")]
+        /// If the device is enabled.
+        enabled -> bool;
+        #[doc(alias = "Seat")]
+        /// The seat that the device belongs to.
+        seat -> Option<String>;
+        #[doc(alias = "Metadata")]
+        /// The metadata for the device.
+        metadata -> HashMap<String, String>;
+        #[doc(alias = "Metadata")]
+        /// The metadata for the device with the values as sent by the daemon.
+        metadata_raw -> HashMap<String, OwnedValue>;
+        #[cfg(feature = "serde_json")]
+        #[doc(alias = "Metadata")]
+        /// The metadata for the device as a JSON object.
+        metadata_json -> serde_json::Value;
+    }
+}
+
+/// A blocking wrapper of the `org.freedesktop.ColorManager.Profile` DBus
+/// object.
+#[derive(Debug, Clone)]
+pub struct Profile<'a>(crate::Profile<'a>);
+
+impl<'a> Profile<'a> {
+    /// Get a reference to the underlying async Profile.
+    pub fn inner(&self) -> &crate::Profile<'a> {
+        &self.0
+    }
+
+    /// Drops the cached property values, see [`crate::Profile::refresh`].
+    pub fn refresh(&self) -> Result<()> {
+        block_on(self.0.refresh())
+    }
+
+    blocking_getters! {
+        #[doc(alias = "ProfileId")]
+        /// The identification hash of the profile.
+        profile_id -> String;
+        #[doc(alias = "Title")]
+        /// The printable title for the profile.
+        title -> String;
+        #[doc(alias = "Metadata")]
+        /// The metadata for the profile.
+        metadata -> HashMap<String, String>;
+        #[doc(alias = "Metadata")]
+        /// The metadata for the profile with the values as sent by the daemon.
+        metadata_raw -> HashMap<String, OwnedValue>;
+        #[cfg(feature = "serde_json")]
+        #[doc(alias = "Metadata")]
+        /// The metadata for the profile as a JSON object.
+        metadata_json -> serde_json::Value;
+        #[doc(alias = "Qualifier")]
+        /// The qualifier for the profile.
+        qualifier -> String;
+        #[doc(alias = "Format")]
+        /// The qualifier format for the profile.
+        format -> String;
+        #[doc(alias = "Kind")]
+        /// The profile kind.
+        kind -> String;
+        #[doc(alias = "Colorspace")]
+        /// The profile colorspace.
+        colorspace -> String;
+        #[doc(alias = "HasVcgt")]
+        /// If the profile has a VCGT entry.
+        has_vcgt -> bool;
+        #[doc(alias = "IsSystemWide")]
+        /// If the profile is installed system wide.
+        is_system_wide -> bool;
+        #[doc(alias = "Filename")]
+        /// The profile filename, if one exists.
+        filename -> String;
+        #[doc(alias = "Created")]
+        /// The date and time the profile was created in UNIX time.
+        created -> u64;
+        #[doc(alias = "Scope")]
+        /// The scope of the profile.
+        scope -> Scope;
+        #[doc(alias = "Owner")]
+        /// The user ID of the account that created the profile.
+        owner -> Owner;
+        #[doc(alias = "Warnings")]
+        /// Any warnings for the profile.
+        warnings -> Vec<String>;
+    }
+}
+
+/// A blocking wrapper of the `org.freedesktop.ColorManager.Sensor` DBus
+/// object.
+#[derive(Debug, Clone)]
+pub struct Sensor<'a>(crate::Sensor<'a>);
+
+impl<'a> Sensor<'a> {
+    /// Get a reference to the underlying async Sensor.
+    pub fn inner(&self) -> &crate::Sensor<'a> {
+        &self.0
+    }
+
+    /// Drops the cached property values, see [`crate::Sensor::refresh`].
+    pub fn refresh(&self) -> Result<()> {
+        block_on(self.0.refresh())
+    }
+
+    blocking_getters! {
+        #[doc(alias = "SensorId")]
+        /// The sensor id string.
+        sensor_id -> String;
+        #[doc(alias = "Kind")]
+        /// The kind of the sensor.
+        kind -> String;
+        #[doc(alias = "State")]
+        /// The state of the sensor.
+        state -> String;
+        #[doc(alias = "Mode")]
+        /// The operating mode of the sensor.
+        mode -> sensor::Mode;
+        #[doc(alias = "Serial")]
+        /// The sensor serial number.
+        serial -> Option<String>;
+        #[doc(alias = "Model")]
+        /// The sensor model.
+        model -> String;
+        #[doc(alias = "Vendor")]
+        /// The sensor vendor.
+        vendor -> String;
+        #[doc(alias = "Native")]
+        /// If the sensor is supported with a native driver.
+        native -> bool;
+        #[doc(alias = "Locked")]
+        /// If the sensor is locked for use by colord.
+        locked -> bool;
+        #[doc(alias = "Capabilities")]
+        /// The capabilities of the sensor.
+        capabilities -> Vec<String>;
+        #[doc(alias = "Metadata")]
+        /// The metadata for the sensor.
+        metadata -> HashMap<String, String>;
+        #[doc(alias = "Metadata")]
+        /// The metadata for the sensor with the values as sent by the daemon.
+        metadata_raw -> HashMap<String, OwnedValue>;
+        #[cfg(feature = "serde_json")]
+        #[doc(alias = "Metadata")]
+        /// The metadata for the sensor as a JSON object.
+        metadata_json -> serde_json::Value;
+    }
+}
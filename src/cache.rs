@@ -0,0 +1,115 @@
+//! Caching of the properties of DBus objects.
+//!
+//! Reading a property is a round-trip to the daemon, applications that show
+//! the same objects repeatedly can keep the last read values and drop them
+//! when the daemon reports a change, see [`ProfileCache`].
+
+use std::collections::HashMap;
+
+use zbus::{zvariant::OwnedObjectPath, Result};
+
+use crate::{Change, Profile};
+
+/// The properties of a profile shown in e.g. a settings panel.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProfileInfo {
+    pub profile_id: String,
+    pub title: String,
+    pub kind: String,
+    pub colorspace: String,
+    pub qualifier: String,
+    pub filename: String,
+    pub has_vcgt: bool,
+    pub is_system_wide: bool,
+}
+
+impl ProfileInfo {
+    /// Reads the properties of `profile`, concurrently.
+    pub async fn read(profile: &Profile<'_>) -> Result<Self> {
+        let (profile_id, title, kind, colorspace, qualifier, filename, has_vcgt, is_system_wide) =
+            futures_util::try_join!(
+                profile.profile_id(),
+                profile.title(),
+                profile.kind(),
+                profile.colorspace(),
+                profile.qualifier(),
+                profile.filename(),
+                profile.has_vcgt(),
+                profile.is_system_wide(),
+            )?;
+
+        Ok(Self {
+            profile_id,
+            title,
+            kind,
+            colorspace,
+            qualifier,
+            filename,
+            has_vcgt,
+            is_system_wide,
+        })
+    }
+}
+
+/// The last read [`ProfileInfo`] of profiles, keyed by their object path.
+///
+/// Entries are read on the first [`ProfileCache::get`] and dropped by
+/// [`ProfileCache::invalidate`], which is meant to be fed the changes reported
+/// by the daemon:
+///
+/// ```no_run
+/// # async fn run(manager: &color_manager::ColorManager<'_>) -> zbus::Result<()> {
+/// use color_manager::cache::ProfileCache;
+/// use futures_util::StreamExt;
+///
+/// // Subscribe before reading, so that no change is missed.
+/// let mut changes = std::pin::pin!(manager.changes().await?);
+/// let mut cache = ProfileCache::default();
+/// for profile in manager.profiles().await? {
+///     println!("{}", cache.get(&profile).await?.title);
+/// }
+/// while let Some(change) = changes.next().await {
+///     cache.invalidate(&change?);
+/// }
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Default)]
+pub struct ProfileCache {
+    entries: HashMap<OwnedObjectPath, ProfileInfo>,
+}
+
+impl ProfileCache {
+    /// Gets the info of `profile`, reading it from the daemon if it is not
+    /// cached.
+    pub async fn get(&mut self, profile: &Profile<'_>) -> Result<&ProfileInfo> {
+        let path = OwnedObjectPath::from(profile.inner().path().to_owned());
+        if !self.entries.contains_key(&path) {
+            let info = ProfileInfo::read(profile).await?;
+            self.entries.insert(path.clone(), info);
+        }
+
+        Ok(&self.entries[&path])
+    }
+
+    /// Gets the cached info of the profile at `path`, without contacting the
+    /// daemon.
+    pub fn cached(&self, path: &OwnedObjectPath) -> Option<&ProfileInfo> {
+        self.entries.get(path)
+    }
+
+    /// Drops the entry of a profile that was changed or removed.
+    pub fn invalidate(&mut self, change: &Change) {
+        match change {
+            Change::ProfileChanged(path) | Change::ProfileRemoved(path) => {
+                self.entries.remove(path);
+            }
+            _ => {}
+        }
+    }
+
+    /// Drops all the entries, e.g. after reconnecting to the daemon.
+    pub fn clear(&mut self) {
+        self.entries.clear();
+    }
+}
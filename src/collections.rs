@@ -0,0 +1,30 @@
+//! Helpers to keep a local list of objects in sync with the daemon.
+//!
+//! [`Device`](crate::Device), [`Profile`](crate::Profile) and
+//! [`Sensor`](crate::Sensor) compare equal when they wrap the same object
+//! path, so an object received from a change signal can be reconciled with a
+//! previously fetched list.
+
+/// Replaces the entry equal to `item` in `list`, or appends `item` if there is
+/// none. Returns the replaced entry.
+///
+/// ```no_run
+/// # async fn run(manager: &color_manager::ColorManager<'_>) -> zbus::Result<()> {
+/// use color_manager::collections::replace_or_push;
+///
+/// let mut devices = manager.devices().await?;
+/// loop {
+///     let device = manager.device_changed().await?;
+///     replace_or_push(&mut devices, device);
+/// }
+/// # }
+/// ```
+pub fn replace_or_push<T: PartialEq>(list: &mut Vec<T>, item: T) -> Option<T> {
+    match list.iter_mut().find(|existing| **existing == item) {
+        Some(existing) => Some(std::mem::replace(existing, item)),
+        None => {
+            list.push(item);
+            None
+        }
+    }
+}
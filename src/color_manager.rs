@@ -1,16 +1,109 @@
 use std::{
-    collections::HashMap,
-    os::unix::io::{AsFd, AsRawFd},
+    collections::{HashMap, HashSet},
+    fs::File,
+    io::{self, Read, Seek, Write},
+    os::unix::io::{AsFd, AsRawFd, BorrowedFd, FromRawFd, RawFd},
+    path::Path,
+    sync::Arc,
+    time::Duration,
 };
 
-use futures_util::StreamExt;
-use zbus::{zvariant::OwnedObjectPath, Result};
+use futures_util::{
+    future::{join_all, try_join, try_join3},
+    Stream, StreamExt,
+};
+use zbus::{
+    names::BusName,
+    zvariant::{Fd, OwnedObjectPath, OwnedValue},
+    Result,
+};
+
+use crate::{
+    config::{self, DESTINATION},
+    device::{self, DeviceProperty},
+    edid::Edid,
+    error, icc,
+    profile::{ProfileProperty, StandardSpace, Warning},
+    sensor::SensorSummary,
+    trace::{self, ProxyExt},
+    ClientConfig, DaemonVersion, Device, Profile, Scope, Sensor,
+};
+
+/// A change of the set of objects managed by the daemon, carrying the path of
+/// the affected object.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Change {
+    DeviceAdded(OwnedObjectPath),
+    DeviceRemoved(OwnedObjectPath),
+    DeviceChanged(OwnedObjectPath),
+    ProfileAdded(OwnedObjectPath),
+    ProfileRemoved(OwnedObjectPath),
+    ProfileChanged(OwnedObjectPath),
+    SensorAdded(OwnedObjectPath),
+    SensorRemoved(OwnedObjectPath),
+}
+
+/// The properties describing the daemon and the system it runs on, see
+/// [`ColorManager::system_info`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SystemInfo {
+    pub daemon_version: String,
+    /// `None` if the daemon predates the `SystemVendor` property.
+    pub vendor: Option<String>,
+    /// `None` if the daemon predates the `SystemModel` property.
+    pub model: Option<String>,
+}
+
+/// Keeps calling the callback given to [`ColorManager::on_change`] for as long
+/// as it is alive.
+#[derive(Debug)]
+#[must_use = "the callback is no longer called once the subscription is dropped"]
+pub struct ChangeSubscription {
+    _task: zbus::Task<()>,
+}
+
+/// A builder for a [`ColorManager`] with a [`ClientConfig`], see
+/// [`ColorManager::builder`].
+#[derive(Debug)]
+pub struct ColorManagerBuilder<'c> {
+    config: ClientConfig,
+    connection: Option<&'c zbus::Connection>,
+}
+
+impl<'c> ColorManagerBuilder<'c> {
+    /// Uses `connection` instead of opening a connection to
+    /// [`ClientConfig::bus`].
+    pub fn connection(mut self, connection: &'c zbus::Connection) -> Self {
+        self.connection = Some(connection);
+        self
+    }
 
-use crate::{Device, Profile, Sensor};
+    /// Creates the ColorManager.
+    pub async fn build<'a>(self) -> Result<ColorManager<'a>> {
+        let config = Arc::new(self.config);
+        match self.connection {
+            Some(connection) => ColorManager::with_config(connection, config).await,
+            None => {
+                let connection = config.connect().await?;
+                ColorManager::with_config(&connection, config).await
+            }
+        }
+    }
+}
+
+impl ProxyExt for ColorManager<'_> {
+    fn proxy(&self) -> &zbus::Proxy<'_> {
+        &self.0
+    }
+
+    fn method_timeout(&self) -> Option<Duration> {
+        self.1.method_timeout
+    }
+}
 
 /// A wrapper of the `org.freedesktop.ColorManager` DBus interface.
 #[derive(Debug)]
-pub struct ColorManager<'a>(zbus::Proxy<'a>);
+pub struct ColorManager<'a>(zbus::Proxy<'a>, Arc<ClientConfig>);
 
 impl<'a> ColorManager<'a> {
     /// Creates a new instance of ColorManager
@@ -22,14 +115,99 @@ impl<'a> ColorManager<'a> {
     /// Creates a new instance of ColorManager using a given connection, the
     /// connection has to be a system connection.
     pub async fn from_connection(connection: &zbus::Connection) -> Result<ColorManager<'a>> {
-        let proxy = zbus::ProxyBuilder::new_bare(connection)
-            .interface("org.freedesktop.ColorManager")?
-            .path("/org/freedesktop/ColorManager")?
-            .destination("org.freedesktop.ColorManager")?
-            .cache_properties(zbus::CacheProperties::No)
-            .build()
+        Self::with_config(connection, Arc::default()).await
+    }
+
+    /// Configures a ColorManager, the settings are passed on to every
+    /// [`Device`], [`Profile`] and [`Sensor`] created from it.
+    ///
+    /// ```no_run
+    /// # async fn run() -> zbus::Result<()> {
+    /// use std::time::Duration;
+    ///
+    /// use color_manager::{ClientConfig, ColorManager};
+    ///
+    /// let config = ClientConfig {
+    ///     method_timeout: Some(Duration::from_secs(5)),
+    ///     ..Default::default()
+    /// };
+    /// let manager = ColorManager::builder(config).build().await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn builder<'c>(config: ClientConfig) -> ColorManagerBuilder<'c> {
+        ColorManagerBuilder {
+            config,
+            connection: None,
+        }
+    }
+
+    pub(crate) async fn with_config(
+        connection: &zbus::Connection,
+        config: Arc<ClientConfig>,
+    ) -> Result<ColorManager<'a>> {
+        let proxy = config
+            .proxy(
+                connection,
+                "org.freedesktop.ColorManager",
+                "/org/freedesktop/ColorManager",
+            )
             .await?;
-        Ok(Self(proxy))
+        Ok(Self(proxy, config))
+    }
+
+    /// Creates a new instance of ColorManager on the connection of `device`.
+    pub async fn from_device(device: &Device<'_>) -> Result<ColorManager<'a>> {
+        Self::with_config(device.inner().connection(), device.shared_config()).await
+    }
+
+    /// Creates a new instance of ColorManager on the connection of `profile`.
+    pub async fn from_profile(profile: &Profile<'_>) -> Result<ColorManager<'a>> {
+        Self::with_config(profile.inner().connection(), profile.shared_config()).await
+    }
+
+    /// Creates a new instance of ColorManager on the connection of `sensor`.
+    pub async fn from_sensor(sensor: &Sensor<'_>) -> Result<ColorManager<'a>> {
+        Self::with_config(sensor.inner().connection(), sensor.shared_config()).await
+    }
+
+    /// Waits until the daemon owns its name on the bus of `connection` and
+    /// creates a ColorManager, e.g. for applications started before colord
+    /// during boot.
+    ///
+    /// Fails with a [`io::ErrorKind::TimedOut`] error if the daemon did not
+    /// appear after `timeout`.
+    pub async fn wait_until_available(
+        connection: &zbus::Connection,
+        timeout: Duration,
+    ) -> Result<ColorManager<'a>> {
+        let dbus = zbus::fdo::DBusProxy::new(connection).await?;
+        let mut owner_changed = dbus
+            .receive_name_owner_changed_with_args(&[(0, DESTINATION)])
+            .await?;
+
+        let name = BusName::try_from(DESTINATION)?;
+        if !dbus.name_has_owner(name).await? {
+            let appeared = async {
+                while let Some(signal) = owner_changed.next().await {
+                    if signal.args()?.new_owner().is_some() {
+                        return Ok(());
+                    }
+                }
+                Err(zbus::Error::InputOutput(
+                    io::Error::from(io::ErrorKind::UnexpectedEof).into(),
+                ))
+            };
+            crate::timeout::timeout(timeout, appeared).await?;
+        }
+
+        Self::from_connection(connection).await
+    }
+
+    /// The real user ID of the current process, as compared against
+    /// [`Device::owner`] by [`Device::is_mine`].
+    pub fn current_uid() -> u32 {
+        crate::owner::current_uid()
     }
 
     /// Get a reference to the underlying Proxy.
@@ -37,25 +215,144 @@ impl<'a> ColorManager<'a> {
         &self.0
     }
 
+    /// The settings the ColorManager was created with.
+    pub fn config(&self) -> &ClientConfig {
+        &self.1
+    }
+
+    pub(crate) fn shared_config(&self) -> Arc<ClientConfig> {
+        self.1.clone()
+    }
+
+    /// Opens a new system connection and rebuilds the proxy on top of it, e.g.
+    /// after the bus daemon was restarted.
+    ///
+    /// A lost connection surfaces as a [`zbus::Error::InputOutput`] error,
+    /// objects created from the old connection have to be fetched again.
+    ///
+    /// ```no_run
+    /// # async fn run(manager: &mut color_manager::ColorManager<'_>) -> zbus::Result<()> {
+    /// let devices = match manager.devices().await {
+    ///     Err(zbus::Error::InputOutput(_)) => {
+    ///         manager.reconnect().await?;
+    ///         manager.devices().await?
+    ///     }
+    ///     devices => devices?,
+    /// };
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn reconnect(&mut self) -> Result<()> {
+        let connection = self.1.connect().await?;
+        *self = Self::with_config(&connection, self.1.clone()).await?;
+
+        Ok(())
+    }
+
     #[doc(alias = "GetDevices")]
     /// Gets a list of all the devices which have assigned color profiles.
     pub async fn devices(&self) -> Result<Vec<Device<'static>>> {
-        let msg = self.inner().call_method("GetDevices", &()).await?;
+        let msg = self.traced_call("GetDevices", &()).await?;
         let reply = msg.body::<Vec<OwnedObjectPath>>()?;
 
-        Device::from_paths(self.inner().connection(), reply).await
+        Device::from_paths(self.inner().connection(), reply, self.shared_config()).await
+    }
+
+    #[doc(alias = "GetDevices")]
+    /// Like [`ColorManager::devices`] but the devices are created lazily as
+    /// the stream is polled, the list of object paths is fetched only once.
+    pub async fn devices_stream(&self) -> Result<impl Stream<Item = Result<Device<'static>>>> {
+        let msg = self.traced_call("GetDevices", &()).await?;
+        let reply = msg.body::<Vec<OwnedObjectPath>>()?;
+
+        Ok(self.lazy_devices(reply))
+    }
+
+    fn lazy_devices(
+        &self,
+        paths: Vec<OwnedObjectPath>,
+    ) -> impl Stream<Item = Result<Device<'static>>> {
+        let connection = self.inner().connection().clone();
+        let config = self.shared_config();
+
+        futures_util::stream::iter(paths).then(move |path| {
+            let connection = connection.clone();
+            let config = config.clone();
+            async move { Device::with_config(&connection, path, config).await }
+        })
+    }
+
+    /// Gets all the devices together with their metadata, the metadata of
+    /// every device is read concurrently.
+    pub async fn devices_metadata(
+        &self,
+    ) -> Result<Vec<(Device<'static>, HashMap<String, String>)>> {
+        let devices = self.devices().await?;
+        let metadata = join_all(devices.iter().map(|device| device.metadata())).await;
+
+        devices
+            .into_iter()
+            .zip(metadata)
+            .map(|(device, metadata)| Ok((device, metadata?)))
+            .collect()
+    }
+
+    #[doc(alias = "GetDevices")]
+    /// Gets the devices that belong to `seat`, e.g. `seat0`. The seat of every
+    /// device is read concurrently.
+    pub async fn devices_for_seat(&self, seat: &str) -> Result<Vec<Device<'static>>> {
+        let devices = self.devices().await?;
+        let seats = join_all(devices.iter().map(|device| device.seat())).await;
+
+        let mut on_seat = Vec::new();
+        for (device, device_seat) in devices.into_iter().zip(seats) {
+            if device_seat?.as_deref() == Some(seat) {
+                on_seat.push(device);
+            }
+        }
+
+        Ok(on_seat)
+    }
+
+    #[doc(alias = "GetDevices")]
+    /// Gets the devices created by the user running the current process, e.g.
+    /// the virtual devices added by a session color applet.
+    pub async fn my_devices(&self) -> Result<Vec<Device<'static>>> {
+        let devices = self.devices().await?;
+        let owned = join_all(devices.iter().map(|device| device.is_mine())).await;
+
+        let mut mine = Vec::new();
+        for (device, owned) in devices.into_iter().zip(owned) {
+            if owned? {
+                mine.push(device);
+            }
+        }
+
+        Ok(mine)
     }
 
     #[doc(alias = "GetDevicesByKind")]
     /// Gets a list of all the devices which have assigned color profiles.
-    pub async fn devices_by_kind(&self, kind: &str) -> Result<Vec<Device>> {
+    pub async fn devices_by_kind(&self, kind: &str) -> Result<Vec<Device<'_>>> {
+        let msg = self.traced_call("GetDevicesByKind", &(kind)).await?;
+        let reply = msg.body::<Vec<OwnedObjectPath>>()?;
+
+        Device::from_paths(self.inner().connection(), reply, self.shared_config()).await
+    }
+
+    #[doc(alias = "GetDevicesByKind")]
+    /// Like [`ColorManager::devices_by_kind`] but the devices are created
+    /// lazily as the stream is polled, see [`ColorManager::devices_stream`].
+    pub async fn devices_by_kind_stream(
+        &self,
+        kind: device::Kind,
+    ) -> Result<impl Stream<Item = Result<Device<'static>>>> {
         let msg = self
-            .inner()
-            .call_method("GetDevicesByKind", &(kind))
+            .traced_call("GetDevicesByKind", &(kind.as_str()))
             .await?;
         let reply = msg.body::<Vec<OwnedObjectPath>>()?;
 
-        Device::from_paths(self.inner().connection(), reply).await
+        Ok(self.lazy_devices(reply))
     }
 
     #[doc(alias = "FindDeviceById")]
@@ -63,75 +360,117 @@ impl<'a> ColorManager<'a> {
     /// ID's may have to be mangled to conform with the DBus path specification.
     /// For instance, a device ID of "cups$34:dev' would have a object path of
     /// "/org/freedesktop/ColorManager/cups_34_dev".
-    pub async fn find_device_by_id(&self, device_id: &str) -> Result<Device> {
-        let msg = self
-            .inner()
-            .call_method("FindDeviceById", &(device_id))
-            .await?;
+    pub async fn find_device_by_id(&self, device_id: &str) -> Result<Device<'_>> {
+        let msg = self.traced_call("FindDeviceById", &(device_id)).await?;
         let reply = msg.body::<OwnedObjectPath>()?;
 
-        Device::new(self.inner().connection(), reply).await
+        Device::with_config(self.inner().connection(), reply, self.shared_config()).await
+    }
+
+    #[doc(alias = "FindDeviceById")]
+    /// Like [`ColorManager::find_device_by_id`] but returns `None` if there is
+    /// no device with the given ID.
+    ///
+    /// Note that the ID is mangled by the daemon, see
+    /// [`ColorManager::find_device_by_id`].
+    pub async fn try_find_device_by_id(&self, device_id: &str) -> Result<Option<Device<'_>>> {
+        error::none_on_error(self.find_device_by_id(device_id).await, error::NOT_FOUND)
     }
 
     #[doc(alias = "FindSensorById")]
     /// Gets a sensor path for the sensor ID.
-    pub async fn find_sensor_by_id(&self, device_id: &str) -> Result<Sensor> {
-        let msg = self
-            .inner()
-            .call_method("FindSensorById", &(device_id))
-            .await?;
+    pub async fn find_sensor_by_id(&self, device_id: &str) -> Result<Sensor<'_>> {
+        let msg = self.traced_call("FindSensorById", &(device_id)).await?;
         let reply = msg.body::<OwnedObjectPath>()?;
 
-        Sensor::new(self.inner().connection(), reply).await
+        Sensor::with_config(self.inner().connection(), reply, self.shared_config()).await
+    }
+
+    #[doc(alias = "FindSensorById")]
+    /// Like [`ColorManager::find_sensor_by_id`] but returns `None` if there is
+    /// no sensor with the given ID.
+    pub async fn try_find_sensor_by_id(&self, sensor_id: &str) -> Result<Option<Sensor<'_>>> {
+        error::none_on_error(self.find_sensor_by_id(sensor_id).await, error::NOT_FOUND)
     }
 
     #[doc(alias = "FindDeviceByProperty")]
     /// Gets a device path for the device with the specified property.
-    pub async fn find_device_by_property(&self, key: &str, value: &str) -> Result<Device> {
+    pub async fn find_device_by_property(&self, key: &str, value: &str) -> Result<Device<'_>> {
         let msg = self
-            .inner()
-            .call_method("FindDeviceByProperty", &(key, value))
+            .traced_call("FindDeviceByProperty", &(key, value))
             .await?;
         let reply = msg.body::<OwnedObjectPath>()?;
 
-        Device::new(self.inner().connection(), reply).await
+        Device::with_config(self.inner().connection(), reply, self.shared_config()).await
+    }
+
+    #[doc(alias = "FindDeviceByProperty")]
+    /// Like [`ColorManager::find_device_by_property`] but restricted to the
+    /// keys the daemon can match on.
+    pub async fn find_device_by_property_typed(
+        &self,
+        property: DeviceProperty,
+        value: &str,
+    ) -> Result<Device<'_>> {
+        self.find_device_by_property(property.as_str(), value).await
     }
 
     #[doc(alias = "FindProfileById")]
     /// Gets a profile path for the profile ID.
-    pub async fn find_profile_by_id(&self, profile_id: &str) -> Result<Profile> {
-        let msg = self
-            .inner()
-            .call_method("FindDeviceByProperty", &(profile_id))
-            .await?;
+    pub async fn find_profile_by_id(&self, profile_id: &str) -> Result<Profile<'_>> {
+        let msg = self.traced_call("FindProfileById", &(profile_id)).await?;
         let reply = msg.body::<OwnedObjectPath>()?;
 
-        Profile::new(self.inner().connection(), reply).await
+        Profile::with_config(self.inner().connection(), reply, self.shared_config()).await
     }
 
     #[doc(alias = "FindProfileByProperty")]
     /// Gets a profile path for the profile with the specified property.
-    pub async fn find_profile_by_property(&self, key: &str, value: &str) -> Result<Profile> {
+    pub async fn find_profile_by_property(&self, key: &str, value: &str) -> Result<Profile<'_>> {
         let msg = self
-            .inner()
-            .call_method("FindProfileByProperty", &(key, value))
+            .traced_call("FindProfileByProperty", &(key, value))
             .await?;
         let reply = msg.body::<OwnedObjectPath>()?;
 
-        Profile::new(self.inner().connection(), reply).await
+        Profile::with_config(self.inner().connection(), reply, self.shared_config()).await
+    }
+
+    #[doc(alias = "FindProfileByProperty")]
+    /// Like [`ColorManager::find_profile_by_property`] but restricted to the
+    /// keys the daemon can match on.
+    pub async fn find_profile_by_property_typed(
+        &self,
+        property: ProfileProperty,
+        value: &str,
+    ) -> Result<Profile<'_>> {
+        self.find_profile_by_property(property.as_str(), value)
+            .await
     }
 
     #[doc(alias = "FindProfileByFilename")]
     /// Gets a profile path for the profile filename, either a fully-qualified
     /// filename with path or just the basename of the profile.
-    pub async fn find_profile_by_filename(&self, file_name: &str) -> Result<Profile> {
+    pub async fn find_profile_by_filename(&self, file_name: &str) -> Result<Profile<'_>> {
         let msg = self
-            .inner()
-            .call_method("FindProfileByFilename", &(file_name))
+            .traced_call("FindProfileByFilename", &(file_name))
             .await?;
         let reply = msg.body::<OwnedObjectPath>()?;
 
-        Profile::new(self.inner().connection(), reply).await
+        Profile::with_config(self.inner().connection(), reply, self.shared_config()).await
+    }
+
+    #[doc(alias = "FindProfileByFilename")]
+    /// Like [`ColorManager::find_profile_by_filename`] but returns `None` if
+    /// there is no profile with the given filename, either a fully-qualified
+    /// filename with path or just the basename of the profile.
+    pub async fn try_find_profile_by_filename(
+        &self,
+        file_name: &str,
+    ) -> Result<Option<Profile<'_>>> {
+        error::none_on_error(
+            self.find_profile_by_filename(file_name).await,
+            error::NOT_FOUND,
+        )
     }
 
     #[doc(alias = "GetStandardSpace")]
@@ -141,35 +480,133 @@ impl<'a> ColorManager<'a> {
     ///
     /// NOTE: only system wide profiles are able to define themselves as
     /// standard spaces.
-    pub async fn standard_space(&self, standard_space: &str) -> Result<Profile> {
+    pub async fn standard_space(&self, standard_space: &str) -> Result<Profile<'_>> {
         let msg = self
-            .inner()
-            .call_method("GetStandardSpace", &(standard_space))
+            .traced_call("GetStandardSpace", &(standard_space))
             .await?;
         let reply = msg.body::<OwnedObjectPath>()?;
 
-        Profile::new(self.inner().connection(), reply).await
+        Profile::with_config(self.inner().connection(), reply, self.shared_config()).await
+    }
+
+    #[doc(alias = "GetStandardSpace")]
+    /// Like [`ColorManager::standard_space`] but returns `None` if no profile
+    /// defines itself as the given standard space.
+    ///
+    /// NOTE: only system wide profiles are able to define themselves as
+    /// standard spaces.
+    pub async fn try_standard_space(&self, standard_space: &str) -> Result<Option<Profile<'_>>> {
+        error::none_on_error(self.standard_space(standard_space).await, error::NOT_FOUND)
+    }
+
+    #[doc(alias = "GetStandardSpace")]
+    /// Like [`ColorManager::standard_space`] but restricted to the spaces known
+    /// by the daemon.
+    pub async fn standard_space_typed(&self, standard_space: StandardSpace) -> Result<Profile<'_>> {
+        self.standard_space(standard_space.as_str()).await
     }
 
     #[doc(alias = "GetSensors")]
     /// Gets a list of all the sensors recognised by the system.
-    pub async fn sensors(&self) -> Result<Vec<Sensor>> {
-        let msg = self.inner().call_method("GetSensors", &()).await?;
+    pub async fn sensors(&self) -> Result<Vec<Sensor<'_>>> {
+        let msg = self.traced_call("GetSensors", &()).await?;
+        let reply = msg.body::<Vec<OwnedObjectPath>>()?;
+
+        Sensor::from_paths(self.inner().connection(), reply, self.shared_config()).await
+    }
+
+    #[doc(alias = "GetSensors")]
+    /// Gets all the sensors together with their model, vendor and state, the
+    /// properties of every sensor are read concurrently.
+    pub async fn sensors_with_state(&self) -> Result<Vec<SensorSummary<'_>>> {
+        let sensors = self.sensors().await?;
+        let summaries = sensors.into_iter().map(|sensor| async move {
+            let (model, vendor, state) =
+                try_join3(sensor.model(), sensor.vendor(), sensor.state_typed()).await?;
+
+            Ok(SensorSummary {
+                sensor,
+                model,
+                vendor,
+                state,
+            })
+        });
+
+        join_all(summaries).await.into_iter().collect()
+    }
+
+    #[doc(alias = "GetSensors")]
+    /// Gets the sensors with a native driver in the daemon, i.e. the ones not
+    /// needing argyllcms. `Native` is read for every sensor concurrently.
+    pub async fn native_sensors(&self) -> Result<Vec<Sensor<'_>>> {
+        let sensors = self.sensors().await?;
+        let native = join_all(sensors.iter().map(|sensor| sensor.native())).await;
+
+        let mut filtered = Vec::new();
+        for (sensor, native) in sensors.into_iter().zip(native) {
+            if native? {
+                filtered.push(sensor);
+            }
+        }
+
+        Ok(filtered)
+    }
+
+    #[doc(alias = "GetProfiles")]
+    /// Gets a list of all the profiles recognised by the system.
+    pub async fn profiles(&self) -> Result<Vec<Profile<'static>>> {
+        let msg = self.traced_call("GetProfiles", &()).await?;
         let reply = msg.body::<Vec<OwnedObjectPath>>()?;
 
-        Sensor::from_paths(self.inner().connection(), reply).await
+        Profile::from_paths(self.inner().connection(), reply, self.shared_config()).await
+    }
+
+    #[doc(alias = "GetProfiles")]
+    /// Gets the profiles that are not installed system wide, i.e. the per-user
+    /// profiles. `IsSystemWide` is read for every profile concurrently.
+    ///
+    /// Unlike [`ColorManager::profiles_by_kind`], which filters on the kind of
+    /// profile such as `display-device`, this filters on where the profile is
+    /// installed.
+    pub async fn user_profiles(&self) -> Result<Vec<Profile<'static>>> {
+        let profiles = self.profiles().await?;
+        let system_wide = join_all(profiles.iter().map(|profile| profile.is_system_wide())).await;
+
+        let mut user = Vec::new();
+        for (profile, system_wide) in profiles.into_iter().zip(system_wide) {
+            if !system_wide? {
+                user.push(profile);
+            }
+        }
+
+        Ok(user)
+    }
+
+    #[doc(alias = "GetProfiles")]
+    /// Gets the profiles that are not assigned to any device, e.g. to clean up
+    /// unused profiles. The profiles of every device are read concurrently.
+    pub async fn orphan_profiles(&self) -> Result<Vec<Profile<'static>>> {
+        let (profiles, devices) = try_join(self.profiles(), self.devices()).await?;
+        let assigned = join_all(devices.iter().map(|device| device.profiles())).await;
+
+        let mut attached = HashSet::new();
+        for device_profiles in assigned {
+            attached.extend(device_profiles?.iter().map(OwnedObjectPath::from));
+        }
+
+        Ok(profiles
+            .into_iter()
+            .filter(|profile| !attached.contains(&OwnedObjectPath::from(profile)))
+            .collect())
     }
 
     #[doc(alias = "GetProfilesByKind")]
     /// Gets a list of all the profiles of a specified type.
-    pub async fn profiles_by_kind(&self, kind: &str) -> Result<Vec<Profile>> {
-        let msg = self
-            .inner()
-            .call_method("GetProfilesByKind", &(kind))
-            .await?;
+    pub async fn profiles_by_kind(&self, kind: &str) -> Result<Vec<Profile<'_>>> {
+        let msg = self.traced_call("GetProfilesByKind", &(kind)).await?;
         let reply = msg.body::<Vec<OwnedObjectPath>>()?;
 
-        Profile::from_paths(self.inner().connection(), reply).await
+        Profile::from_paths(self.inner().connection(), reply, self.shared_config()).await
     }
 
     #[doc(alias = "CreateProfileWithFd")]
@@ -186,25 +623,200 @@ impl<'a> ColorManager<'a> {
     /// Using a file descriptor in addition to the filename allows the daemon to
     /// parse the ICC profile without re-opening it, which allows the daemon to
     /// read files inside the users home directory in a SELinux environment.
-    pub async fn create_profile_with_fd<F: AsFd>(
+    ///
+    /// The properties can be given as borrowed or owned strings, e.g. a
+    /// `HashMap<String, String>`.
+    ///
+    /// The file descriptor is borrowed from `handle` for the whole duration of
+    /// the call, so `handle` can be a temporary such as `File::open(path)?`.
+    /// It is duplicated when sent to the daemon, the caller keeps ownership of
+    /// `handle` and its file descriptor is not closed by this method.
+    pub async fn create_profile_with_fd<F, P, K, V>(
         &self,
         profile_id: &str,
-        scope: &str,
+        scope: Scope,
         handle: F,
-        properties: HashMap<&str, &str>,
-    ) -> Result<Profile> {
-        let raw_fd = handle.as_fd().as_raw_fd();
+        properties: P,
+    ) -> Result<Profile<'_>>
+    where
+        F: AsFd,
+        P: IntoIterator<Item = (K, V)>,
+        K: AsRef<str>,
+        V: AsRef<str>,
+    {
+        let properties = properties.into_iter().collect::<Vec<_>>();
+        let properties = properties
+            .iter()
+            .map(|(key, value)| (key.as_ref(), value.as_ref()))
+            .collect::<HashMap<&str, &str>>();
+        // Keep the fd borrowed until the call has returned. A `BorrowedFd` is
+        // never negative, so there is no invalid value to check for here.
+        let fd = handle.as_fd();
+        debug_assert!(fd.try_clone_to_owned().is_ok(), "fd is not open");
         let msg = self
-            .inner()
-            .call_method(
+            .traced_call(
                 "CreateProfileWithFd",
-                &(profile_id, scope, raw_fd, properties),
+                &(
+                    profile_id,
+                    scope.as_wire_str(),
+                    Fd::from(fd.as_raw_fd()),
+                    properties,
+                ),
             )
             .await?;
         let reply = msg.body::<OwnedObjectPath>()?;
         msg.take_fds();
 
-        Profile::new(self.inner().connection(), reply).await
+        Profile::with_config(self.inner().connection(), reply, self.shared_config()).await
+    }
+
+    #[doc(alias = "CreateProfileWithFd")]
+    /// Like [`ColorManager::create_profile_with_fd`] but also reads the
+    /// warnings the daemon found while parsing the profile, e.g.
+    /// [`Warning::DescriptionMissing`].
+    pub async fn create_profile_checked<F, P, K, V>(
+        &self,
+        profile_id: &str,
+        scope: Scope,
+        handle: F,
+        properties: P,
+    ) -> Result<(Profile<'_>, Vec<Warning>)>
+    where
+        F: AsFd,
+        P: IntoIterator<Item = (K, V)>,
+        K: AsRef<str>,
+        V: AsRef<str>,
+    {
+        let profile = self
+            .create_profile_with_fd(profile_id, scope, handle, properties)
+            .await?;
+        let warnings = profile.warnings_typed().await?;
+
+        Ok((profile, warnings))
+    }
+
+    #[doc(alias = "CreateProfileWithFd")]
+    /// Like [`ColorManager::create_profile_with_fd`] but opens the file at
+    /// `path` for reading and sends its file descriptor.
+    ///
+    /// Unlike [`ColorManager::create_profile_from_file`] the ICC header is not
+    /// checked and the `Filename` property is not set.
+    pub async fn create_profile_with_path<P, K, V>(
+        &self,
+        profile_id: &str,
+        scope: Scope,
+        path: &Path,
+        properties: P,
+    ) -> crate::Result<Profile<'_>>
+    where
+        P: IntoIterator<Item = (K, V)>,
+        K: AsRef<str>,
+        V: AsRef<str>,
+    {
+        let file = File::open(path)?;
+        let profile = self
+            .create_profile_with_fd(profile_id, scope, &file, properties)
+            .await?;
+
+        Ok(profile)
+    }
+
+    #[doc(alias = "CreateProfileWithFd")]
+    /// Like [`ColorManager::create_profile_with_fd`] but takes a raw file
+    /// descriptor, e.g. one received through FFI.
+    ///
+    /// # Safety
+    ///
+    /// `fd` must be an open file descriptor and stay open until the returned
+    /// future has completed or was dropped. It is not closed by this method.
+    pub async unsafe fn create_profile_with_raw_fd<P, K, V>(
+        &self,
+        profile_id: &str,
+        scope: Scope,
+        fd: RawFd,
+        properties: P,
+    ) -> Result<Profile<'_>>
+    where
+        P: IntoIterator<Item = (K, V)>,
+        K: AsRef<str>,
+        V: AsRef<str>,
+    {
+        // SAFETY: the caller guarantees that `fd` is open for the whole call.
+        let fd = unsafe { BorrowedFd::borrow_raw(fd) };
+
+        self.create_profile_with_fd(profile_id, scope, fd, properties)
+            .await
+    }
+
+    #[doc(alias = "CreateProfileWithFd")]
+    /// Creates a profile out of the ICC data in `data`, e.g. a profile
+    /// generated in memory.
+    ///
+    /// The data is written to an anonymous memory backed file created with
+    /// `memfd_create`, whose file descriptor is sent to the daemon, so nothing
+    /// is written to the filesystem.
+    pub async fn create_profile_from_bytes(
+        &self,
+        profile_id: &str,
+        scope: Scope,
+        data: &[u8],
+    ) -> crate::Result<Profile<'_>> {
+        // SAFETY: the name is a valid nul terminated string.
+        let fd =
+            unsafe { libc::memfd_create(c"color-manager-profile".as_ptr(), libc::MFD_CLOEXEC) };
+        if fd < 0 {
+            return Err(io::Error::last_os_error().into());
+        }
+        // SAFETY: `fd` was just created and is not owned by anything else.
+        let mut file = unsafe { File::from_raw_fd(fd) };
+        file.write_all(data)?;
+        file.rewind()?;
+
+        let profile = self
+            .create_profile_with_fd(profile_id, scope, &file, HashMap::<&str, &str>::new())
+            .await?;
+
+        Ok(profile)
+    }
+
+    #[doc(alias = "CreateProfileWithFd")]
+    /// Creates a profile for the ICC file at `path`.
+    ///
+    /// The profile ID is derived from the contents of the file as the daemon
+    /// does for the profiles it finds, `icc-` followed by their MD5 checksum,
+    /// and the `Filename` property is set to the absolute path of the file.
+    /// Files with the same name in different directories thus get different
+    /// IDs, while creating a profile for a file whose contents are already
+    /// known to the daemon, e.g. the same file twice, fails with an
+    /// `AlreadyExists` error.
+    ///
+    /// The ICC header of the file is checked before contacting the daemon, and
+    /// [`Error::InvalidIcc`](crate::Error::InvalidIcc) is returned if the file
+    /// is clearly not an ICC profile.
+    pub async fn create_profile_from_file(
+        &self,
+        path: &Path,
+        scope: Scope,
+    ) -> crate::Result<Profile<'_>> {
+        let mut file = File::open(path)?;
+        let mut data = Vec::new();
+        file.read_to_end(&mut data)?;
+        let header = &data[..data.len().min(icc::HEADER_SIZE)];
+        icc::validate_header(header, data.len() as u64)?;
+        file.rewind()?;
+
+        let path = path.canonicalize()?;
+        let filename = path.to_str().ok_or_else(|| {
+            io::Error::new(io::ErrorKind::InvalidInput, "Filename is not valid UTF-8")
+        })?;
+        let profile_id = format!("icc-{}", icc::checksum(&data));
+
+        let properties = HashMap::from([("Filename", filename)]);
+        let profile = self
+            .create_profile_with_fd(&profile_id, scope, &file, properties)
+            .await?;
+
+        Ok(profile)
     }
 
     #[doc(alias = "CreateProfile")]
@@ -213,52 +825,140 @@ impl<'a> ColorManager<'a> {
     /// have read access to profiles in the users home directory.
     pub async fn create_profile(
         &self,
-        scope: &str,
+        scope: Scope,
         properties: HashMap<&str, &str>,
-    ) -> Result<Profile> {
+    ) -> Result<Profile<'_>> {
         let msg = self
-            .inner()
-            .call_method("CreateProfile", &(scope, properties))
+            .traced_call("CreateProfile", &(scope.as_wire_str(), properties))
             .await?;
         let reply = msg.body::<OwnedObjectPath>()?;
 
-        Profile::new(self.inner().connection(), reply).await
+        Profile::with_config(self.inner().connection(), reply, self.shared_config()).await
     }
+
     #[doc(alias = "CreateDevice")]
     ///  Creates a device.
     ///
+    /// The daemon expects the ID of the device before the scope, which this
+    /// does not send, use [`ColorManager::create_device_with_id`] instead.
+    #[deprecated(note = "the daemon requires a device ID, use `create_device_with_id`")]
+    pub async fn create_device(
+        &self,
+        scope: Scope,
+        properties: HashMap<&str, &str>,
+    ) -> Result<Device<'_>> {
+        let msg = self
+            .traced_call("CreateDevice", &(scope.as_wire_str(), properties))
+            .await?;
+        let reply = msg.body::<OwnedObjectPath>()?;
+
+        Device::with_config(self.inner().connection(), reply, self.shared_config()).await
+    }
+
+    #[doc(alias = "CreateDevice")]
+    /// Creates a device with the given ID, e.g. `xrandr-DEL-DELL U2415`.
+    ///
     /// If the device has profiles added to it in the past, and that profiles
     /// exists already, then the new device will be automatically have profiles
     /// added to the device. To prevent this from happening, remove the
     /// assignment by doing RemoveProfile on the relevant device object.
-    pub async fn create_device(
+    ///
+    /// See [`TempDevice`](crate::workflow::TempDevice) to delete a temporary
+    /// device once it is no longer needed.
+    pub async fn create_device_with_id(
         &self,
-        scope: &str,
+        device_id: &str,
+        scope: Scope,
         properties: HashMap<&str, &str>,
-    ) -> Result<Device> {
+    ) -> Result<Device<'_>> {
         let msg = self
-            .inner()
-            .call_method("CreateDevice", &(scope, properties))
+            .traced_call(
+                "CreateDevice",
+                &(device_id, scope.as_wire_str(), properties),
+            )
             .await?;
         let reply = msg.body::<OwnedObjectPath>()?;
 
-        Device::new(self.inner().connection(), reply).await
+        Device::with_config(self.inner().connection(), reply, self.shared_config()).await
+    }
+
+    #[doc(alias = "CreateDevice")]
+    /// Creates a display device from the EDID of the monitor, the model,
+    /// vendor and serial of the device are read from the base block, see
+    /// [`Edid`].
+    ///
+    /// The vendor is the PNP ID of the manufacturer, e.g. `DEL`, and the ID of
+    /// the device is [`Edid::device_id`]. The session, e.g.
+    /// gnome-settings-daemon, registers displays with the vendor name the PNP
+    /// ID stands for, e.g. `Dell Inc.`, so the devices created here are not
+    /// the ones the session created for the same monitor.
+    pub async fn create_display_from_edid(
+        &self,
+        edid: &[u8],
+        scope: Scope,
+    ) -> crate::Result<Device<'_>> {
+        let edid = Edid::parse(edid)?;
+        let model = edid.model();
+        let serial = edid.serial();
+
+        let mut properties = HashMap::from([
+            (
+                DeviceProperty::Kind.as_str(),
+                device::Kind::Display.as_str(),
+            ),
+            (DeviceProperty::Model.as_str(), model.as_str()),
+            (DeviceProperty::Vendor.as_str(), edid.manufacturer.as_str()),
+        ]);
+        if let Some(serial) = &serial {
+            properties.insert(DeviceProperty::Serial.as_str(), serial);
+        }
+
+        let device = self
+            .create_device_with_id(&edid.device_id(), scope, properties)
+            .await?;
+
+        Ok(device)
     }
 
     #[doc(alias = "DeleteDevice")]
     /// Deletes a device.
-    pub async fn delete_device(&self, device: Device<'_>) -> Result<()> {
-        self.inner().call_method("DeleteDevice", &(device)).await?;
+    ///
+    /// Returns [`Error::NotFound`](crate::Error::NotFound) if the device was
+    /// already deleted and [`Error::PermissionDenied`](crate::Error::PermissionDenied)
+    /// if the caller is not allowed to delete it.
+    pub async fn delete_device(&self, device: &Device<'_>) -> crate::Result<()> {
+        self.traced_call("DeleteDevice", &(device))
+            .await
+            .map_err(|err| error::discriminate(err, device.inner().path().as_str()))?;
+
+        Ok(())
+    }
+
+    #[doc(alias = "DeleteDevice")]
+    /// Deletes the device with the given ID.
+    ///
+    /// Returns [`Error::NotFound`](crate::Error::NotFound) if there is no such
+    /// device.
+    pub async fn delete_device_by_id(&self, device_id: &str) -> crate::Result<()> {
+        let device = self
+            .try_find_device_by_id(device_id)
+            .await?
+            .ok_or_else(|| crate::Error::NotFound(device_id.to_owned()))?;
+        self.delete_device(&device).await?;
 
         Ok(())
     }
 
     #[doc(alias = "DeleteProfile")]
     /// Deletes a profile.
-    pub async fn delete_profile(&self, profile: Profile<'_>) -> Result<()> {
-        self.inner()
-            .call_method("DeleteProfile", &(profile))
-            .await?;
+    ///
+    /// Returns [`Error::NotFound`](crate::Error::NotFound) if the profile was
+    /// already deleted and [`Error::PermissionDenied`](crate::Error::PermissionDenied)
+    /// if the caller is not allowed to delete it.
+    pub async fn delete_profile(&self, profile: &Profile<'_>) -> crate::Result<()> {
+        self.traced_call("DeleteProfile", &(profile))
+            .await
+            .map_err(|err| error::discriminate(err, profile.inner().path().as_str()))?;
 
         Ok(())
     }
@@ -266,6 +966,9 @@ impl<'a> ColorManager<'a> {
     #[doc(alias = "Changed")]
     /// Some value on the interface or the number of devices or profiles has
     /// changed.
+    ///
+    /// The signal has no arguments, use [`ColorManager::changes`] to know
+    /// which object changed.
     pub async fn changed(&self) -> Result<()> {
         let mut stream = self.inner().receive_signal("Changed").await?;
         stream
@@ -276,9 +979,65 @@ impl<'a> ColorManager<'a> {
         Ok(())
     }
 
+    #[doc(alias = "Changed")]
+    /// Calls `f` each time the `Changed` signal is received, from a task
+    /// spawned on the executor of the connection.
+    ///
+    /// The task runs until the returned [`ChangeSubscription`] is dropped.
+    pub async fn on_change<F>(&self, mut f: F) -> Result<ChangeSubscription>
+    where
+        F: FnMut() + Send + 'static,
+    {
+        let mut stream = self.inner().receive_signal("Changed").await?;
+        let task = self.inner().connection().executor().spawn(
+            async move {
+                while stream.next().await.is_some() {
+                    f();
+                }
+            },
+            "Changed",
+        );
+
+        Ok(ChangeSubscription { _task: task })
+    }
+
+    /// Waits for the next device, profile or sensor to be added, removed or
+    /// changed.
+    #[deprecated(note = "changes between two calls are missed, use `changes`")]
+    pub async fn next_change(&self) -> Result<Change> {
+        let mut changes = std::pin::pin!(self.changes().await?);
+        changes
+            .next()
+            .await
+            .ok_or(zbus::Error::Failure("No response".into()))?
+    }
+
+    /// The devices, profiles and sensors being added, removed or changed.
+    ///
+    /// The stream is subscribed once, so no change is missed between two
+    /// polls of the stream.
+    pub async fn changes(&self) -> Result<impl Stream<Item = Result<Change>> + '_> {
+        let stream = self.inner().receive_all_signals().await?;
+
+        Ok(stream.filter_map(|message| async move {
+            let change = match message.member().as_ref().map(|member| member.as_str()) {
+                Some("DeviceAdded") => Change::DeviceAdded,
+                Some("DeviceRemoved") => Change::DeviceRemoved,
+                Some("DeviceChanged") => Change::DeviceChanged,
+                Some("ProfileAdded") => Change::ProfileAdded,
+                Some("ProfileRemoved") => Change::ProfileRemoved,
+                Some("ProfileChanged") => Change::ProfileChanged,
+                Some("SensorAdded") => Change::SensorAdded,
+                Some("SensorRemoved") => Change::SensorRemoved,
+                _ => return None,
+            };
+            Some(message.body::<OwnedObjectPath>().map(change))
+        }))
+    }
+
     #[doc(alias = "DeviceAdded")]
     /// A device has been added.
-    pub async fn device_added(&self) -> Result<Device> {
+    pub async fn device_added(&self) -> Result<Device<'_>> {
         let mut stream = self.inner().receive_signal("DeviceAdded").await?;
         let message = stream
             .next()
@@ -286,12 +1045,12 @@ impl<'a> ColorManager<'a> {
             .ok_or(zbus::Error::Failure("No response".into()))?;
         let content = message.body::<OwnedObjectPath>()?;
 
-        Device::new(self.inner().connection(), content).await
+        Device::with_config(self.inner().connection(), content, self.shared_config()).await
     }
 
     #[doc(alias = "DeviceChanged")]
     /// A device has changed.
-    pub async fn device_changed(&self) -> Result<Device> {
+    pub async fn device_changed(&self) -> Result<Device<'_>> {
         let mut stream = self.inner().receive_signal("DeviceChanged").await?;
         let message = stream
             .next()
@@ -299,12 +1058,12 @@ impl<'a> ColorManager<'a> {
             .ok_or(zbus::Error::Failure("No response".into()))?;
         let content = message.body::<OwnedObjectPath>()?;
 
-        Device::new(self.inner().connection(), content).await
+        Device::with_config(self.inner().connection(), content, self.shared_config()).await
     }
 
     #[doc(alias = "ProfileAdded")]
     /// A profile has been added.
-    pub async fn profile_added(&self) -> Result<Profile> {
+    pub async fn profile_added(&self) -> Result<Profile<'_>> {
         let mut stream = self.inner().receive_signal("ProfileAdded").await?;
         let message = stream
             .next()
@@ -312,12 +1071,12 @@ impl<'a> ColorManager<'a> {
             .ok_or(zbus::Error::Failure("No response".into()))?;
         let content = message.body::<OwnedObjectPath>()?;
 
-        Profile::new(self.inner().connection(), content).await
+        Profile::with_config(self.inner().connection(), content, self.shared_config()).await
     }
 
     #[doc(alias = "ProfileRemoved")]
     /// A profile has been removed.
-    pub async fn profile_removed(&self) -> Result<Profile> {
+    pub async fn profile_removed(&self) -> Result<Profile<'_>> {
         let mut stream = self.inner().receive_signal("ProfileRemoved").await?;
         let message = stream
             .next()
@@ -325,12 +1084,12 @@ impl<'a> ColorManager<'a> {
             .ok_or(zbus::Error::Failure("No response".into()))?;
         let content = message.body::<OwnedObjectPath>()?;
 
-        Profile::new(self.inner().connection(), content).await
+        Profile::with_config(self.inner().connection(), content, self.shared_config()).await
     }
 
     #[doc(alias = "SensorAdded")]
     /// A sensor has been added.
-    pub async fn sensor_added(&self) -> Result<Sensor> {
+    pub async fn sensor_added(&self) -> Result<Sensor<'_>> {
         let mut stream = self.inner().receive_signal("SensorAdded").await?;
         let message = stream
             .next()
@@ -338,12 +1097,12 @@ impl<'a> ColorManager<'a> {
             .ok_or(zbus::Error::Failure("No response".into()))?;
         let content = message.body::<OwnedObjectPath>()?;
 
-        Sensor::new(self.inner().connection(), content).await
+        Sensor::with_config(self.inner().connection(), content, self.shared_config()).await
     }
 
     #[doc(alias = "SensorRemoved")]
     /// A sensor has been removed.
-    pub async fn sensor_removed(&self) -> Result<Sensor> {
+    pub async fn sensor_removed(&self) -> Result<Sensor<'_>> {
         let mut stream = self.inner().receive_signal("SensorRemoved").await?;
         let message = stream
             .next()
@@ -351,12 +1110,12 @@ impl<'a> ColorManager<'a> {
             .ok_or(zbus::Error::Failure("No response".into()))?;
         let content = message.body::<OwnedObjectPath>()?;
 
-        Sensor::new(self.inner().connection(), content).await
+        Sensor::with_config(self.inner().connection(), content, self.shared_config()).await
     }
 
     #[doc(alias = "ProfileChanged")]
     /// A profile has been changed.
-    pub async fn profile_changed(&self) -> Result<Profile> {
+    pub async fn profile_changed(&self) -> Result<Profile<'_>> {
         let mut stream = self.inner().receive_signal("ProfileChanged").await?;
         let message = stream
             .next()
@@ -364,24 +1123,81 @@ impl<'a> ColorManager<'a> {
             .ok_or(zbus::Error::Failure("No response".into()))?;
         let content = message.body::<OwnedObjectPath>()?;
 
-        Profile::new(self.inner().connection(), content).await
+        Profile::with_config(self.inner().connection(), content, self.shared_config()).await
+    }
+
+    #[doc(alias = "GetAll")]
+    /// Gets the daemon version, system vendor and system model in a single
+    /// call, the vendor and model are `None` if the daemon does not have them.
+    pub async fn system_info(&self) -> Result<SystemInfo> {
+        let properties = config::properties_proxy(self.inner()).await?;
+        let interface = self.inner().interface().as_str();
+        let msg = trace::with_timeout(
+            self.method_timeout(),
+            properties.traced_call("GetAll", &(interface)),
+        )
+        .await?;
+        let mut reply = msg.body::<HashMap<String, OwnedValue>>()?;
+
+        let mut take = |name: &str| -> Result<Option<String>> {
+            reply
+                .remove(name)
+                .map(|value| Ok(String::try_from(value)?))
+                .transpose()
+        };
+        let daemon_version = take("DaemonVersion")?
+            .ok_or_else(|| zbus::fdo::Error::UnknownProperty("DaemonVersion".to_owned()))?;
+
+        Ok(SystemInfo {
+            daemon_version,
+            vendor: take("SystemVendor")?,
+            model: take("SystemModel")?,
+        })
     }
 
     #[doc(alias = "DaemonVersion")]
     /// The daemon version.
     pub async fn daemon_version(&self) -> Result<String> {
-        self.inner().get_property("DaemonVersion").await
+        self.traced_property("DaemonVersion").await
+    }
+
+    #[doc(alias = "DaemonVersion")]
+    /// The daemon version, parsed so it can be compared against the versions
+    /// that introduced a feature.
+    ///
+    /// Returns [`Error::InvalidVersion`](crate::Error::InvalidVersion) if the
+    /// version cannot be parsed.
+    pub async fn daemon_version_typed(&self) -> crate::Result<DaemonVersion> {
+        Ok(self.daemon_version().await?.parse()?)
+    }
+
+    /// Maps the failure to read a property added in the daemon version `since`
+    /// to `Ok(None)` if the running daemon is older. Depending on the daemon,
+    /// a missing property is reported as `UnknownProperty` or `InvalidArgs`,
+    /// so the version is only read once `result` failed.
+    async fn introduced_in<T>(&self, since: DaemonVersion, result: Result<T>) -> Result<Option<T>> {
+        match result {
+            Ok(value) => Ok(Some(value)),
+            Err(err) => match self.daemon_version_typed().await {
+                Ok(version) if version < since => Ok(None),
+                _ => Err(err),
+            },
+        }
     }
 
     #[doc(alias = "SystemVendor")]
-    /// The system vendor.
-    pub async fn system_vendor(&self) -> Result<String> {
-        self.inner().get_property("SystemVendor").await
+    /// The system vendor, `None` if the daemon is older than colord 0.1.27,
+    /// which added the property.
+    pub async fn system_vendor(&self) -> Result<Option<String>> {
+        let result = self.traced_property("SystemVendor").await;
+        self.introduced_in(DaemonVersion::SYSTEM_INFO, result).await
     }
 
     #[doc(alias = "SystemModel")]
-    /// The system vendor.
-    pub async fn system_model(&self) -> Result<String> {
-        self.inner().get_property("SystemModel").await
+    /// The system model, `None` if the daemon is older than colord 0.1.27,
+    /// which added the property.
+    pub async fn system_model(&self) -> Result<Option<String>> {
+        let result = self.traced_property("SystemModel").await;
+        self.introduced_in(DaemonVersion::SYSTEM_INFO, result).await
     }
 }
@@ -0,0 +1,126 @@
+use std::{
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+use zbus::zvariant::ObjectPath;
+
+/// The name the daemon owns on the bus.
+pub(crate) const DESTINATION: &str = "org.freedesktop.ColorManager";
+
+/// The bus to connect to, see [`ClientConfig::bus`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum BusKind {
+    /// The system bus, where colord runs.
+    #[default]
+    System,
+    /// The session bus, e.g. for a test instance of the daemon.
+    Session,
+}
+
+/// The settings shared by a [`ColorManager`](crate::ColorManager) and every
+/// [`Device`](crate::Device), [`Profile`](crate::Profile) and
+/// [`Sensor`](crate::Sensor) created from it, see
+/// [`ColorManager::builder`](crate::ColorManager::builder).
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct ClientConfig {
+    /// If the values of properties are cached and kept up to date with the
+    /// `PropertiesChanged` signal, instead of being read on each call.
+    pub cache_properties: bool,
+    /// How long to wait for the reply of a method call or property read
+    /// before failing with a [`std::io::ErrorKind::TimedOut`] error.
+    pub method_timeout: Option<Duration>,
+    /// The bus name of the daemon, `org.freedesktop.ColorManager` if `None`.
+    pub destination: Option<String>,
+    /// The bus to connect to when no connection is given.
+    pub bus: BusKind,
+}
+
+impl ClientConfig {
+    /// The bus name of the daemon.
+    pub fn destination(&self) -> &str {
+        self.destination.as_deref().unwrap_or(DESTINATION)
+    }
+
+    /// Opens a connection to [`ClientConfig::bus`].
+    pub(crate) async fn connect(&self) -> zbus::Result<zbus::Connection> {
+        match self.bus {
+            BusKind::System => zbus::Connection::system().await,
+            BusKind::Session => zbus::Connection::session().await,
+        }
+    }
+
+    /// Builds a proxy for `interface` of the daemon object at `path`.
+    pub(crate) async fn proxy<'a, P>(
+        &self,
+        connection: &zbus::Connection,
+        interface: &'static str,
+        path: P,
+    ) -> zbus::Result<zbus::Proxy<'a>>
+    where
+        P: TryInto<ObjectPath<'a>>,
+        P::Error: Into<zbus::Error>,
+    {
+        let cache = if self.cache_properties {
+            zbus::CacheProperties::Lazily
+        } else {
+            zbus::CacheProperties::No
+        };
+
+        zbus::ProxyBuilder::new_bare(connection)
+            .interface(interface)?
+            .path(path)?
+            .destination(self.destination().to_owned())?
+            .cache_properties(cache)
+            .build()
+            .await
+    }
+}
+
+/// Builds a proxy for the `org.freedesktop.DBus.Properties` interface of the
+/// object of `proxy`, e.g. to receive `PropertiesChanged`.
+pub(crate) async fn properties_proxy<'a>(proxy: &zbus::Proxy<'a>) -> zbus::Result<zbus::Proxy<'a>> {
+    zbus::ProxyBuilder::new_bare(proxy.connection())
+        .interface("org.freedesktop.DBus.Properties")?
+        .path(proxy.path().to_owned())?
+        .destination(proxy.destination().to_owned())?
+        .cache_properties(zbus::CacheProperties::No)
+        .build()
+        .await
+}
+
+/// The proxy that the properties of an object are read from once its cached
+/// values were dropped, see e.g. [`Device::refresh`](crate::Device::refresh).
+///
+/// The property cache of a [`zbus::Proxy`] cannot be cleared, so a new proxy
+/// for the same object takes its place for reading properties. Clones of an
+/// object share it.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct PropertyCache(Arc<Mutex<Option<zbus::Proxy<'static>>>>);
+
+impl PropertyCache {
+    /// The proxy to read properties from instead of the one of the object, if
+    /// it was refreshed.
+    pub(crate) fn proxy(&self) -> Option<zbus::Proxy<'static>> {
+        self.0.lock().unwrap().clone()
+    }
+
+    /// Replaces the cached values of the properties of `interface` on the
+    /// object of `proxy`, does nothing if `config` does not cache them.
+    pub(crate) async fn refresh(
+        &self,
+        config: &ClientConfig,
+        proxy: &zbus::Proxy<'_>,
+        interface: &'static str,
+    ) -> zbus::Result<()> {
+        if !config.cache_properties {
+            return Ok(());
+        }
+        let fresh = config
+            .proxy(proxy.connection(), interface, proxy.path().to_owned())
+            .await?;
+        *self.0.lock().unwrap() = Some(fresh);
+
+        Ok(())
+    }
+}
@@ -1,37 +1,28 @@
-use std::collections::HashMap;
+use std::{
+    collections::{HashMap, HashSet},
+    hash::{Hash, Hasher},
+    sync::Arc,
+    time::Duration,
+};
 
-use futures_util::StreamExt;
+use futures_util::{
+    future::{join_all, try_join3},
+    StreamExt,
+};
 use serde::{Deserialize, Serialize};
 use zbus::{
-    zvariant::{ObjectPath, OwnedObjectPath, SerializeDict, Type},
+    zvariant::{ObjectPath, OwnedObjectPath, OwnedValue, Type},
     Result,
 };
 
-use crate::{Profile, Scope};
-
-// TODO Use PascalCase
-#[derive(SerializeDict, Type, Default)]
-#[zvariant(signature = "dict")]
-struct DeviceProperties<'a> {
-    created: u64,
-    modified: u64,
-    model: String,
-    serial: String,
-    vendor: String,
-    colorspace: String,
-    kind: String,
-    device_id: String,
-    profiles: Vec<Profile<'a>>,
-    mode: Mode,
-    format: String,
-    scope: Scope,
-    owner: u32,
-    enabled: bool,
-    seat: String,
-    embedded: bool,
-    metadata: HashMap<String, String>,
-    profiling_inhibitors: Vec<String>,
-}
+use crate::{
+    config::PropertyCache,
+    error,
+    qualifier::{self, Format, QualifierQuery},
+    trace::ProxyExt,
+    wire::{self, impl_from_owned_value},
+    ClientConfig, Owner, Profile, PropertyValue, Scope,
+};
 
 #[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Type)]
 #[zvariant(signature = "s")]
@@ -44,68 +35,116 @@ pub enum Kind {
     Webcam,
 }
 
-impl From<zbus::zvariant::OwnedValue> for Kind {
-    fn from(value: zbus::zvariant::OwnedValue) -> Self {
-        match value
-            .downcast_ref::<zbus::zvariant::Str>()
-            .unwrap()
-            .as_str()
-        {
-            "scanner" => Self::Scanner,
-            "Camera" => Self::Camera,
-            _ => Self::Display,
+impl Kind {
+    /// The wire form of the kind, e.g. `display`.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Scanner => "scanner",
+            Self::Display => "display",
+            Self::Camera => "camera",
+            Self::Printer => "printer",
+            Self::Webcam => "webcam",
         }
     }
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Type)]
+impl_from_owned_value!(Kind, Self::Display);
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Type, Default)]
 #[zvariant(signature = "s")]
 #[serde(rename_all = "lowercase")]
+/// How a profile is attached to a device, unknown values are treated as
+/// [`Relation::Hard`].
 pub enum Relation {
     Soft,
+    #[default]
     Hard,
 }
 
-impl Default for Relation {
-    fn default() -> Self {
-        Self::Hard
-    }
-}
+impl_from_owned_value!(Relation, Self::default());
 
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Type)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Type, Default)]
 #[zvariant(signature = "s")]
 #[serde(rename_all = "lowercase")]
 pub enum Mode {
     Virtual,
     Physical,
+    #[default]
     Unknown,
 }
 
-impl Default for Mode {
-    fn default() -> Self {
-        Self::Unknown
+impl Mode {
+    /// The wire form of the mode, e.g. `virtual`.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Virtual => "virtual",
+            Self::Physical => "physical",
+            Self::Unknown => "unknown",
+        }
     }
 }
 
-impl From<zbus::zvariant::OwnedValue> for Mode {
-    fn from(value: zbus::zvariant::OwnedValue) -> Self {
-        match value
-            .downcast_ref::<zbus::zvariant::Str>()
-            .unwrap()
-            .as_str()
-        {
-            "virtual" => Self::Virtual,
-            "physical" => Self::Physical,
-            _ => Self::default(),
+impl_from_owned_value!(Mode, Self::default());
+
+/// Mangles a device ID the same way the daemon does when building the object
+/// path of a device, e.g. `cups$34:dev` becomes `cups_34_dev`.
+///
+/// Every byte that is not an ASCII letter, digit or `_` is replaced by `_`.
+pub fn mangle_id(device_id: &str) -> String {
+    device_id
+        .bytes()
+        .map(|byte| {
+            if byte.is_ascii_alphanumeric() || byte == b'_' {
+                byte as char
+            } else {
+                '_'
+            }
+        })
+        .collect()
+}
+
+/// Reverses [`mangle_id`] for the last component of a device object path,
+/// `None` if it contains a `_`, which could stand for any other byte.
+fn unmangle_path(path: &str) -> Option<String> {
+    let mangled = path.strip_prefix("/org/freedesktop/ColorManager/devices/")?;
+    if mangled.is_empty() || mangled.contains('_') {
+        return None;
+    }
+
+    Some(mangled.to_owned())
+}
+
+/// The keys that can be used to find a device by property.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DeviceProperty {
+    Model,
+    Vendor,
+    Serial,
+    Kind,
+    DeviceId,
+    /// A metadata key, e.g. `XRANDR_name`.
+    Metadata(String),
+}
+
+impl DeviceProperty {
+    /// The key used on the wire, e.g. `DeviceId`.
+    pub fn as_str(&self) -> &str {
+        match self {
+            Self::Model => "Model",
+            Self::Vendor => "Vendor",
+            Self::Serial => "Serial",
+            Self::Kind => "Kind",
+            Self::DeviceId => "DeviceId",
+            Self::Metadata(key) => key,
         }
     }
 }
 
-#[derive(Type, Debug)]
+#[derive(Type, Clone)]
 #[zvariant(signature = "o")]
 #[doc(alias = "org.freedesktop.ColorManager.Device")]
 /// A wrapper of the `org.freedesktop.ColorManager.Device` DBus object.
-pub struct Device<'a>(zbus::Proxy<'a>);
+pub struct Device<'a>(zbus::Proxy<'a>, Arc<ClientConfig>, PropertyCache);
 
 impl<'a> Device<'a> {
     pub async fn new<P>(connection: &zbus::Connection, object_path: P) -> Result<Device<'a>>
@@ -113,19 +152,76 @@ impl<'a> Device<'a> {
         P: TryInto<ObjectPath<'a>>,
         P::Error: Into<zbus::Error>,
     {
-        let inner = zbus::ProxyBuilder::new_bare(connection)
-            .interface("org.freedesktop.ColorManager.Device")?
-            .path(object_path)?
-            .destination("org.freedesktop.ColorManager")?
-            .cache_properties(zbus::CacheProperties::No)
-            .build()
+        Self::with_config(connection, object_path, Arc::default()).await
+    }
+
+    pub(crate) async fn with_config<P>(
+        connection: &zbus::Connection,
+        object_path: P,
+        config: Arc<ClientConfig>,
+    ) -> Result<Device<'a>>
+    where
+        P: TryInto<ObjectPath<'a>>,
+        P::Error: Into<zbus::Error>,
+    {
+        let inner = config
+            .proxy(
+                connection,
+                "org.freedesktop.ColorManager.Device",
+                object_path,
+            )
             .await?;
-        Ok(Self(inner))
+        Ok(Self(inner, config, PropertyCache::default()))
+    }
+
+    /// Creates a device from its ID without asking the daemon for its path.
+    ///
+    /// Only devices created by root, such as the ones added by the daemon
+    /// itself, can be found this way, as the daemon appends the user name and
+    /// ID to the path of devices created by other users. Use
+    /// [`ColorManager::find_device_by_id`](crate::ColorManager::find_device_by_id)
+    /// otherwise.
+    pub async fn from_id(connection: &zbus::Connection, device_id: &str) -> Result<Device<'a>> {
+        let path = format!(
+            "/org/freedesktop/ColorManager/devices/{}",
+            mangle_id(device_id)
+        );
+
+        Self::new(connection, path).await
+    }
+
+    /// Rebuilds the proxy of the device on the same connection and object
+    /// path, dropping any cached property values, e.g. after
+    /// [`ColorManager::device_changed`](crate::ColorManager::device_changed)
+    /// reported a change of this device.
+    pub async fn reload(self) -> Result<Device<'a>> {
+        let path = self.inner().path().to_owned();
+
+        Self::with_config(self.inner().connection(), path, self.shared_config()).await
+    }
+
+    /// If this is the device at `path`, e.g. the path carried by a
+    /// [`Change`](crate::Change).
+    pub fn matches_path(&self, path: &ObjectPath<'_>) -> bool {
+        self.inner().path() == path
+    }
+
+    /// Guesses the device ID from the object path without contacting the
+    /// daemon, e.g. for logging.
+    ///
+    /// The mangling of the ID into the path is lossy, every byte that is not
+    /// an ASCII letter or digit becomes a `_`, so `None` is returned whenever
+    /// the path contains one. This includes the paths of devices created by
+    /// users other than root, which end in `_{user}_{uid}`. Use
+    /// [`Device::device_id`] to get the actual ID.
+    pub fn id_from_path(&self) -> Option<String> {
+        unmangle_path(self.inner().path().as_str())
     }
 
     pub(crate) async fn from_paths<P>(
         connection: &zbus::Connection,
         paths: Vec<P>,
+        config: Arc<ClientConfig>,
     ) -> Result<Vec<Device<'a>>>
     where
         P: TryInto<ObjectPath<'a>>,
@@ -133,25 +229,88 @@ impl<'a> Device<'a> {
     {
         let mut items = Vec::with_capacity(paths.capacity());
         for path in paths.into_iter() {
-            items.push(Self::new(connection, path).await?);
+            items.push(Self::with_config(connection, path, config.clone()).await?);
         }
         Ok(items)
     }
 
-    pub fn inner(&self) -> &zbus::Proxy {
+    pub fn inner(&self) -> &zbus::Proxy<'_> {
         &self.0
     }
 
+    /// The settings this object was created with.
+    pub fn config(&self) -> &ClientConfig {
+        &self.1
+    }
+
+    pub(crate) fn shared_config(&self) -> Arc<ClientConfig> {
+        self.1.clone()
+    }
+
+    /// Drops the property values cached with
+    /// [`ClientConfig::cache_properties`], the next reads get them from the
+    /// daemon again. Does nothing if the properties are not cached.
+    ///
+    /// Clones of the device are refreshed too.
+    pub async fn refresh(&self) -> Result<()> {
+        self.2
+            .refresh(&self.1, &self.0, "org.freedesktop.ColorManager.Device")
+            .await
+    }
+
     #[doc(alias = "SetProperty")]
     /// Sets a property on the object.
     pub async fn set_property(&self, property_name: &str, property_value: &str) -> Result<()> {
-        self.inner()
-            .call_method("SetProperty", &(property_name, property_value))
+        self.traced_call("SetProperty", &(property_name, property_value))
             .await?;
 
         Ok(())
     }
 
+    #[doc(alias = "SetProperty")]
+    /// Like [`Device::set_property`] but converts a typed value to the string
+    /// expected by the daemon.
+    ///
+    /// `Kind` takes a [`Kind`], `Mode` a [`Mode`], and `Model`, `Vendor`,
+    /// `Serial`, `Colorspace`, `Format`, `Seat` and metadata keys take
+    /// strings. The enabled state is not a property, use
+    /// [`Device::set_enabled`] instead.
+    pub async fn set_property_value<V>(&self, property_name: &str, property_value: V) -> Result<()>
+    where
+        V: PropertyValue,
+    {
+        self.set_property(property_name, &property_value.to_property_string())
+            .await
+    }
+
+    #[doc(alias = "SetProperty")]
+    /// Sets the device model string.
+    pub async fn set_model(&self, model: &str) -> Result<()> {
+        self.set_property(DeviceProperty::Model.as_str(), model)
+            .await
+    }
+
+    #[doc(alias = "SetProperty")]
+    /// Sets the device vendor string.
+    pub async fn set_vendor(&self, vendor: &str) -> Result<()> {
+        self.set_property(DeviceProperty::Vendor.as_str(), vendor)
+            .await
+    }
+
+    #[doc(alias = "SetProperty")]
+    /// Sets the device serial string.
+    pub async fn set_serial(&self, serial: &str) -> Result<()> {
+        self.set_property(DeviceProperty::Serial.as_str(), serial)
+            .await
+    }
+
+    #[doc(alias = "SetProperty")]
+    /// Sets the device kind.
+    pub async fn set_kind(&self, kind: Kind) -> Result<()> {
+        self.set_property(DeviceProperty::Kind.as_str(), kind.as_str())
+            .await
+    }
+
     #[doc(alias = "AddProfile")]
     /// Adds a profile to the device. The profile must have been previously
     /// created.
@@ -160,9 +319,7 @@ impl<'a> Device<'a> {
     /// datadase, so that if the device and profile happen to both exist in the
     /// future, the profiles are auto-added to the device.
     pub async fn add_profile(&self, relation: Relation, profile: &Profile<'_>) -> Result<()> {
-        self.inner()
-            .call_method("AddProfile", &(relation, profile))
-            .await?;
+        self.traced_call("AddProfile", &(relation, profile)).await?;
 
         Ok(())
     }
@@ -180,9 +337,7 @@ impl<'a> Device<'a> {
     /// user to remove old or obsolete profiles from any color control panel
     /// without having to delete them.
     pub async fn remove_profile(&self, profile: &Profile<'_>) -> Result<()> {
-        self.inner()
-            .call_method("RemoveProfile", &(profile))
-            .await?;
+        self.traced_call("RemoveProfile", &(profile)).await?;
 
         Ok(())
     }
@@ -190,9 +345,7 @@ impl<'a> Device<'a> {
     #[doc(alias = "MakeProfileDefault")]
     /// Sets the default profile for a device.
     pub async fn make_profile_default(&self, profile: &Profile<'_>) -> Result<()> {
-        self.inner()
-            .call_method("MakeProfileDefault", &(profile))
-            .await?;
+        self.traced_call("MakeProfileDefault", &(profile)).await?;
 
         Ok(())
     }
@@ -201,14 +354,67 @@ impl<'a> Device<'a> {
     /// Gets a single profile object path for a qualifier.
     ///
     /// The search term can contain `*` and `?` wildcards.
-    pub async fn profile_for_qualifiers(&self, qualifiers: &[&str]) -> Result<Profile> {
+    pub async fn profile_for_qualifiers(&self, qualifiers: &[&str]) -> Result<Profile<'_>> {
         let msg = self
-            .inner()
-            .call_method("GetProfileForQualifiers", &(qualifiers))
+            .traced_call("GetProfileForQualifiers", &(qualifiers))
             .await?;
         let reply = msg.body::<OwnedObjectPath>()?;
 
-        Profile::new(self.inner().connection(), reply).await
+        Profile::with_config(self.inner().connection(), reply, self.shared_config()).await
+    }
+
+    #[doc(alias = "GetProfileForQualifiers")]
+    /// Like [`Device::profile_for_qualifiers`] but the qualifiers can be given
+    /// as borrowed or owned strings, e.g. a `Vec<String>`.
+    pub async fn profile_for_qualifiers_iter<I, S>(&self, qualifiers: I) -> Result<Profile<'_>>
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        let qualifiers = qualifiers.into_iter().collect::<Vec<_>>();
+        let qualifiers = qualifiers.iter().map(AsRef::as_ref).collect::<Vec<&str>>();
+
+        self.profile_for_qualifiers(&qualifiers).await
+    }
+
+    #[doc(alias = "GetProfileForQualifiers")]
+    /// Gets the profile matching `query`, e.g. any output mode with
+    /// `QualifierQuery::new().exact("RGB")?.any().exact("300dpi")?`.
+    ///
+    /// The query is sent as a single qualifier, `RGB.*.300dpi` for the example.
+    pub async fn profile_for_query(&self, query: &QualifierQuery) -> Result<Profile<'_>> {
+        self.profile_for_qualifiers(&[&query.to_string()]).await
+    }
+
+    #[doc(alias = "GetProfileForQualifiers")]
+    /// Gets all the profiles of the device matching any of the qualifiers.
+    ///
+    /// Unlike [`Device::profile_for_qualifiers`] the matching is done on the
+    /// client side with [`qualifier::matches`], which follows the daemon, and
+    /// the profiles are ordered by the first qualifier they match.
+    pub async fn profiles_matching_qualifiers(
+        &self,
+        qualifiers: &[&str],
+    ) -> Result<Vec<Profile<'static>>> {
+        let mut candidates = Vec::new();
+        for profile in self.profiles().await? {
+            let qualifier = profile.qualifier().await?;
+            candidates.push((profile, qualifier));
+        }
+
+        let mut matches = Vec::new();
+        for pattern in qualifiers {
+            let mut i = 0;
+            while i < candidates.len() {
+                if qualifier::matches(pattern, &candidates[i].1) {
+                    matches.push(candidates.remove(i).0);
+                } else {
+                    i += 1;
+                }
+            }
+        }
+
+        Ok(matches)
     }
 
     #[doc(alias = "GetProfileRelation")]
@@ -216,14 +422,68 @@ impl<'a> Device<'a> {
     ///
     /// The search term can contain `*` and `?` wildcards.
     pub async fn profile_relation(&self, profile: &Profile<'_>) -> Result<Relation> {
-        let msg = self
-            .inner()
-            .call_method("GetProfileRelation", &(profile))
-            .await?;
+        let msg = self.traced_call("GetProfileRelation", &(profile)).await?;
 
         msg.body()
     }
 
+    #[doc(alias = "GetProfileRelation")]
+    /// Gets the relation of a profile to the device, or `None` if the profile
+    /// is not attached to the device.
+    pub async fn try_profile_relation(&self, profile: &Profile<'_>) -> Result<Option<Relation>> {
+        error::none_on_error(
+            self.profile_relation(profile).await,
+            "org.freedesktop.ColorManager.Device.ProfileDoesNotExist",
+        )
+    }
+
+    /// Gets the profiles of the device together with their relation, in the
+    /// order of [`Device::profiles`] so the first one is the default profile.
+    ///
+    /// The relations are resolved concurrently, profiles that were removed from
+    /// the device in the meantime are skipped.
+    pub async fn profile_relations(&self) -> Result<Vec<(Profile<'static>, Relation)>> {
+        let profiles = self.profiles().await?;
+        let relations = join_all(
+            profiles
+                .iter()
+                .map(|profile| self.try_profile_relation(profile)),
+        )
+        .await;
+
+        let mut profile_relations = Vec::with_capacity(profiles.len());
+        for (profile, relation) in profiles.into_iter().zip(relations) {
+            let Some(relation) = relation? else {
+                #[cfg(feature = "tracing")]
+                tracing::warn!(
+                    path = %profile.inner().path(),
+                    "profile was removed from the device, skipping it"
+                );
+                continue;
+            };
+            profile_relations.push((profile, relation));
+        }
+
+        Ok(profile_relations)
+    }
+
+    /// Gets the profiles of the device grouped by their relation, as `(hard,
+    /// soft)`, see [`Device::profile_relations`].
+    pub async fn profiles_by_relation(
+        &self,
+    ) -> Result<(Vec<Profile<'static>>, Vec<Profile<'static>>)> {
+        let mut hard = Vec::new();
+        let mut soft = Vec::new();
+        for (profile, relation) in self.profile_relations().await? {
+            match relation {
+                Relation::Hard => hard.push(profile),
+                Relation::Soft => soft.push(profile),
+            }
+        }
+
+        Ok((hard, soft))
+    }
+
     #[doc(alias = "ProfilingInhibit")]
     /// Adds an inhibit on all profiles for this device.
     ///
@@ -237,7 +497,7 @@ impl<'a> Device<'a> {
     /// If the calling program exits without calling `ProfilingUninhibit` then
     /// the inhibit is automatically removed.
     pub async fn profiling_inhibit(&self) -> Result<()> {
-        self.inner().call_method("ProfilingInhibit", &()).await?;
+        self.traced_call("ProfilingInhibit", &()).await?;
 
         Ok(())
     }
@@ -248,7 +508,7 @@ impl<'a> Device<'a> {
     /// This method should be used when profiling has finished and normal device
     /// matching behaviour should resume.
     pub async fn profiling_uninhibit(&self) -> Result<()> {
-        self.inner().call_method("ProfilingUninhibit", &()).await?;
+        self.traced_call("ProfilingUninhibit", &()).await?;
 
         Ok(())
     }
@@ -256,13 +516,116 @@ impl<'a> Device<'a> {
     #[doc(alias = "SetEnabled")]
     /// Sets the device enable state.
     pub async fn set_enabled(&self, enabled: bool) -> Result<()> {
-        self.inner().call_method("SetEnabled", &(enabled)).await?;
+        self.traced_call("SetEnabled", &(enabled)).await?;
 
         Ok(())
     }
 
+    #[doc(alias = "SetEnabled")]
+    /// Sets the device enable state and returns the state read back from the
+    /// daemon.
+    pub async fn set_enabled_checked(&self, enabled: bool) -> Result<bool> {
+        self.set_enabled(enabled).await?;
+
+        self.enabled().await
+    }
+
+    /// Flips the device enable state and returns the new state.
+    pub async fn toggle_enabled(&self) -> Result<bool> {
+        let enabled = self.enabled().await?;
+
+        self.set_enabled_checked(!enabled).await
+    }
+
+    #[doc(alias = "SetEnabled")]
+    /// Sets the device enable state and waits until the daemon reports it,
+    /// so that the new state is committed when this returns. Returns at once
+    /// if the device is already in that state.
+    ///
+    /// Fails with a [`std::io::ErrorKind::TimedOut`] error if the state was
+    /// not reported after `timeout`.
+    pub async fn set_enabled_and_wait(&self, enabled: bool, timeout: Duration) -> Result<()> {
+        let mut stream = self.inner().receive_signal("Changed").await?;
+        self.set_enabled(enabled).await?;
+
+        crate::timeout::timeout(timeout, async {
+            loop {
+                if self.enabled().await? == enabled {
+                    return Ok(());
+                }
+                stream
+                    .next()
+                    .await
+                    .ok_or(zbus::Error::Failure("No response".into()))?;
+            }
+        })
+        .await
+    }
+
+    #[doc(alias = "Changed")]
+    /// Waits until `profile` is part of the profiles of the device, e.g. after
+    /// calling [`Device::add_profile`].
+    ///
+    /// Fails with a [`std::io::ErrorKind::TimedOut`] error if the profile was
+    /// not attached after `timeout`.
+    pub async fn wait_for_profile(&self, profile: &Profile<'_>, timeout: Duration) -> Result<()> {
+        let mut stream = self.inner().receive_signal("Changed").await?;
+
+        crate::timeout::timeout(timeout, async {
+            loop {
+                let profiles = self.profiles().await?;
+                if profiles
+                    .iter()
+                    .any(|attached| attached.inner().path() == profile.inner().path())
+                {
+                    return Ok(());
+                }
+                stream
+                    .next()
+                    .await
+                    .ok_or(zbus::Error::Failure("No response".into()))?;
+            }
+        })
+        .await
+    }
+
+    #[doc(alias = "RemoveProfile")]
+    /// Removes `profile` from the device, see [`Device::remove_profile`], and
+    /// waits until it is no longer part of the profiles of the device.
+    ///
+    /// Fails with a [`std::io::ErrorKind::TimedOut`] error if the profile was
+    /// still attached after `timeout`.
+    pub async fn remove_profile_and_wait(
+        &self,
+        profile: &Profile<'_>,
+        timeout: Duration,
+    ) -> Result<()> {
+        let mut stream = self.inner().receive_signal("Changed").await?;
+        self.remove_profile(profile).await?;
+
+        crate::timeout::timeout(timeout, async {
+            loop {
+                let profiles = self.profiles().await?;
+                if !profiles
+                    .iter()
+                    .any(|attached| attached.inner().path() == profile.inner().path())
+                {
+                    return Ok(());
+                }
+                stream
+                    .next()
+                    .await
+                    .ok_or(zbus::Error::Failure("No response".into()))?;
+            }
+        })
+        .await
+    }
+
     #[doc(alias = "Changed")]
     /// Some value on the interface has changed.
+    ///
+    /// The signal has no arguments, re-read the properties of the device to
+    /// know what changed.
     pub async fn changed(&self) -> Result<()> {
         let mut stream = self.inner().receive_signal("Changed").await?;
         stream
@@ -276,49 +639,49 @@ impl<'a> Device<'a> {
     #[doc(alias = "Created")]
     /// The date the device was created.
     pub async fn created(&self) -> Result<u64> {
-        self.inner().get_property("Created").await
+        self.traced_property("Created").await
     }
 
     #[doc(alias = "Modified")]
     /// The date the device was created.
     pub async fn modified(&self) -> Result<u64> {
-        self.inner().get_property("Modified").await
+        self.traced_property("Modified").await
     }
 
     #[doc(alias = "Model")]
     /// The device model string.
     pub async fn model(&self) -> Result<String> {
-        self.inner().get_property("Model").await
+        self.traced_property("Model").await
     }
 
     #[doc(alias = "Serial")]
     /// The device serial string.
     pub async fn serial(&self) -> Result<String> {
-        self.inner().get_property("Serial").await
+        self.traced_property("Serial").await
     }
 
     #[doc(alias = "Vendor")]
     /// The device vendor string.
     pub async fn vendor(&self) -> Result<String> {
-        self.inner().get_property("Vendor").await
+        self.traced_property("Vendor").await
     }
 
     #[doc(alias = "Colorspace")]
     /// The device colorspace string.
     pub async fn colorspace(&self) -> Result<String> {
-        self.inner().get_property("Colorspace").await
+        self.traced_property("Colorspace").await
     }
 
     #[doc(alias = "Kind")]
     /// The device kind string.
     pub async fn kind(&self) -> Result<Kind> {
-        self.inner().get_property("Kind").await
+        self.traced_property("Kind").await
     }
 
     #[doc(alias = "DeviceId")]
     /// The device id string.
     pub async fn device_id(&self) -> Result<String> {
-        self.inner().get_property("DeviceId").await
+        self.traced_property("DeviceId").await
     }
 
     #[doc(alias = "Profiles")]
@@ -329,11 +692,10 @@ impl<'a> Device<'a> {
     /// should be applied.
     pub async fn profiles(&self) -> Result<Vec<Profile<'static>>> {
         let reply = self
-            .inner()
-            .get_property::<Vec<OwnedObjectPath>>("Profiles")
+            .traced_property::<Vec<OwnedObjectPath>>("Profiles")
             .await?;
 
-        Profile::from_paths(self.inner().connection(), reply).await
+        Profile::from_paths(self.inner().connection(), reply, self.shared_config()).await
     }
 
     #[doc(alias = "Mode")]
@@ -349,7 +711,7 @@ impl<'a> Device<'a> {
     /// a 'physical' device. This can happen if a printer is saved and then
     /// restored at next boot before the CUPS daemon is running.
     pub async fn mode(&self) -> Result<Mode> {
-        self.inner().get_property::<Mode>("Mode").await
+        self.traced_property::<Mode>("Mode").await
     }
 
     // TODO Is this an enum?
@@ -357,19 +719,46 @@ impl<'a> Device<'a> {
     /// The qualifier format for the device, e.g.
     /// `ColorModel.OutputMode.OutputResolution`.
     pub async fn format(&self) -> Result<String> {
-        self.inner().get_property("Format").await
+        self.traced_property("Format").await
+    }
+
+    #[doc(alias = "Format")]
+    /// Like [`Device::format`] but split into its components.
+    pub async fn format_parsed(&self) -> Result<Format> {
+        self.format().await.map(Format::from)
     }
 
     #[doc(alias = "Scope")]
     /// The scope of the device.
     pub async fn scope(&self) -> Result<Scope> {
-        self.inner().get_property("Scope").await
+        self.traced_property("Scope").await
     }
 
     #[doc(alias = "Owner")]
     /// The user ID of the account that created the device.
-    pub async fn owner(&self) -> Result<u32> {
-        self.inner().get_property("Owner").await
+    pub async fn owner(&self) -> Result<Owner> {
+        self.traced_property::<Owner>("Owner").await
+    }
+
+    #[doc(alias = "Owner")]
+    /// If the device was created by the account with the user ID `uid`.
+    pub async fn is_owned_by(&self, uid: u32) -> Result<bool> {
+        Ok(self.owner().await?.uid() == uid)
+    }
+
+    #[doc(alias = "Owner")]
+    /// If the device was created by the user running the current process, see
+    /// [`ColorManager::current_uid`](crate::ColorManager::current_uid).
+    pub async fn is_mine(&self) -> Result<bool> {
+        self.is_owned_by(crate::owner::current_uid()).await
+    }
+
+    #[cfg(feature = "users")]
+    #[doc(alias = "Owner")]
+    /// The name of the account that created the device, `None` if the user ID
+    /// cannot be resolved.
+    pub async fn owner_name(&self) -> Result<Option<String>> {
+        Ok(self.owner().await?.name())
     }
 
     #[doc(alias = "Enabled")]
@@ -379,35 +768,99 @@ impl<'a> Device<'a> {
     /// called. If the enabled state is changed then this is reflected for all
     /// users and persistent across reboots.
     pub async fn enabled(&self) -> Result<bool> {
-        self.inner().get_property("Enabled").await
+        self.traced_property("Enabled").await
     }
 
     #[doc(alias = "Seat")]
-    /// The seat that the device belongs to, or an empty string for none or
-    /// unknown.
-    pub async fn seat(&self) -> Result<String> {
-        self.inner().get_property("Seat").await
+    /// The seat that the device belongs to, `None` if the device has no seat
+    /// or it is unknown.
+    pub async fn seat(&self) -> Result<Option<String>> {
+        error::optional_property(self.traced_property("Seat").await)
     }
 
     #[doc(alias = "Embedded")]
     /// If the device is embedded into the hardware itself, for example the
     /// internal webcam or laptop screen.
     pub async fn embedded(&self) -> Result<String> {
-        self.inner().get_property("Embedded").await
+        self.traced_property("Embedded").await
     }
 
     #[doc(alias = "Metadata")]
     /// The metadata for the device, which may include optional keys like
     /// `XRANDR_name`.
+    ///
+    /// Entries whose value is not a string are skipped, see
+    /// [`Device::metadata_raw`].
     pub async fn metadata(&self) -> Result<HashMap<String, String>> {
-        self.inner().get_property("Metadata").await
+        Ok(wire::string_metadata(self.metadata_raw().await?))
+    }
+
+    #[cfg(feature = "serde_json")]
+    #[doc(alias = "Metadata")]
+    /// The metadata for the device as a JSON object, see [`Device::metadata`].
+    pub async fn metadata_json(&self) -> Result<serde_json::Value> {
+        Ok(wire::metadata_json(self.metadata().await?))
+    }
+
+    #[doc(alias = "Metadata")]
+    /// The metadata for the device with the values as sent by the daemon.
+    pub async fn metadata_raw(&self) -> Result<HashMap<String, OwnedValue>> {
+        self.traced_property("Metadata").await
     }
 
     #[doc(alias = "ProfilingInhibitors")]
     /// The bus names of all the clients that have inhibited the device for
     /// profiling. e.g. `[ ":1.99", ":1.109" ]`.
     pub async fn profiling_inhibitors(&self) -> Result<Vec<String>> {
-        self.inner().get_property("ProfilingInhibitors").await
+        self.traced_property("ProfilingInhibitors").await
+    }
+
+    #[doc(alias = "ProfilingInhibitors")]
+    /// If any client has inhibited the device for profiling.
+    pub async fn is_profiling_inhibited(&self) -> Result<bool> {
+        Ok(!self.profiling_inhibitors().await?.is_empty())
+    }
+
+    /// If the device can be profiled right now, that is if it:
+    ///
+    /// - is a display, a printer or a camera,
+    /// - is enabled,
+    /// - has not been inhibited for profiling by any client.
+    ///
+    /// The properties are read concurrently.
+    pub async fn can_be_profiled(&self) -> Result<bool> {
+        // The raw string, as kinds not known to this crate read as a display.
+        let (kind, enabled, inhibitors) = try_join3(
+            self.traced_property::<String>("Kind"),
+            self.enabled(),
+            self.profiling_inhibitors(),
+        )
+        .await?;
+        let profilable = [Kind::Display, Kind::Printer, Kind::Camera]
+            .iter()
+            .any(|profilable| profilable.as_str() == kind);
+
+        Ok(profilable && enabled && inhibitors.is_empty())
+    }
+}
+
+impl ProxyExt for Device<'_> {
+    fn proxy(&self) -> &zbus::Proxy<'_> {
+        &self.0
+    }
+
+    fn method_timeout(&self) -> Option<Duration> {
+        self.1.method_timeout
+    }
+
+    fn property_proxy(&self) -> Option<zbus::Proxy<'static>> {
+        self.2.proxy()
+    }
+}
+
+impl From<&Device<'_>> for OwnedObjectPath {
+    fn from(device: &Device<'_>) -> Self {
+        device.inner().path().to_owned().into()
     }
 }
 
@@ -419,3 +872,93 @@ impl<'a> Serialize for Device<'a> {
         ObjectPath::serialize(self.inner().path(), serializer)
     }
 }
+
+/// Devices are compared by their object path.
+impl PartialEq for Device<'_> {
+    fn eq(&self, other: &Self) -> bool {
+        self.matches_path(other.inner().path())
+    }
+}
+
+impl Eq for Device<'_> {}
+
+impl Hash for Device<'_> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.inner().path().hash(state);
+    }
+}
+
+/// Only the object path is printed, e.g.
+/// `Device("/org/freedesktop/ColorManager/...")`.
+impl std::fmt::Debug for Device<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_tuple("Device")
+            .field(&self.inner().path().as_str())
+            .finish()
+    }
+}
+
+/// Compares two snapshots of the devices, e.g. returned by
+/// [`ColorManager::devices`](crate::ColorManager::devices), and returns the
+/// devices that were `(added, removed)`.
+pub fn diff<'a>(old: &[Device<'a>], new: &[Device<'a>]) -> (Vec<Device<'a>>, Vec<Device<'a>>) {
+    let old_paths: HashSet<&ObjectPath<'_>> = old.iter().map(|d| d.inner().path()).collect();
+    let new_paths: HashSet<&ObjectPath<'_>> = new.iter().map(|d| d.inner().path()).collect();
+
+    let added = new
+        .iter()
+        .filter(|device| !old_paths.contains(device.inner().path()))
+        .cloned()
+        .collect();
+    let removed = old
+        .iter()
+        .filter(|device| !new_paths.contains(device.inner().path()))
+        .cloned()
+        .collect();
+
+    (added, removed)
+}
+
+#[cfg(test)]
+mod tests {
+    use zbus::zvariant::{OwnedValue, Value};
+
+    use super::*;
+
+    #[test]
+    fn relation_from_owned_value() {
+        let soft = OwnedValue::from(Value::from("soft"));
+        assert_eq!(Relation::from(soft), Relation::Soft);
+
+        let hard = OwnedValue::from(Value::from("hard"));
+        assert_eq!(Relation::from(hard), Relation::Hard);
+
+        let other = OwnedValue::from(Value::from("unexpected"));
+        assert_eq!(Relation::from(other), Relation::Hard);
+    }
+
+    #[test]
+    fn mangle() {
+        assert_eq!(mangle_id("cups$34:dev"), "cups_34_dev");
+        assert_eq!(mangle_id("xrandr-Lenovo_Group"), "xrandr_Lenovo_Group");
+        assert_eq!(mangle_id("sysfs-é"), "sysfs___");
+    }
+
+    #[test]
+    fn unmangle() {
+        let path = |id: &str| format!("/org/freedesktop/ColorManager/devices/{}", mangle_id(id));
+
+        assert_eq!(unmangle_path(&path("xrandr")), Some("xrandr".to_owned()));
+        assert_eq!(unmangle_path(&path("cups42")), Some("cups42".to_owned()));
+        assert_eq!(unmangle_path(&path("xrandr-Foo")), None);
+        assert_eq!(unmangle_path(&path("cups$34:dev")), None);
+        assert_eq!(
+            unmangle_path("/org/freedesktop/ColorManager/devices/xrandr_user_1000"),
+            None
+        );
+        assert_eq!(
+            unmangle_path("/org/freedesktop/ColorManager/profiles/icc"),
+            None
+        );
+    }
+}
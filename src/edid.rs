@@ -0,0 +1,176 @@
+//! Minimal EDID parsing, see the VESA E-EDID specification section 3.
+//!
+//! Only the 128 byte base block is read, extension blocks are ignored.
+
+use crate::Error;
+
+/// The size of the EDID base block in bytes.
+pub const BASE_BLOCK_SIZE: usize = 128;
+
+const HEADER: [u8; 8] = [0x00, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0x00];
+
+/// The offsets of the four 18 byte display descriptors.
+const DESCRIPTORS: [usize; 4] = [54, 72, 90, 108];
+
+/// The identification of a monitor read from its EDID, see
+/// [`ColorManager::create_display_from_edid`](crate::ColorManager::create_display_from_edid).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Edid {
+    /// The three letter PNP ID of the manufacturer, e.g. `DEL`.
+    pub manufacturer: String,
+    /// The manufacturer product code.
+    pub product_code: u16,
+    /// The numeric serial number, zero if unused.
+    pub serial_number: u32,
+    /// The monitor name descriptor, if any.
+    pub monitor_name: Option<String>,
+    /// The serial number descriptor, if any.
+    pub serial: Option<String>,
+}
+
+impl Edid {
+    /// Parses the base block of `bytes`.
+    ///
+    /// Returns [`Error::InvalidEdid`] if the block is too short, does not
+    /// start with the EDID header or its checksum is wrong.
+    pub fn parse(bytes: &[u8]) -> Result<Self, Error> {
+        if bytes.len() < BASE_BLOCK_SIZE {
+            return Err(Error::InvalidEdid("Data is smaller than an EDID block"));
+        }
+        let block = &bytes[..BASE_BLOCK_SIZE];
+        if block[..8] != HEADER {
+            return Err(Error::InvalidEdid("Missing EDID header"));
+        }
+        if block.iter().fold(0u8, |sum, byte| sum.wrapping_add(*byte)) != 0 {
+            return Err(Error::InvalidEdid("Wrong checksum"));
+        }
+
+        // Three 5 bit letters, `1` being `A`.
+        let id = u16::from_be_bytes([block[8], block[9]]);
+        let manufacturer = [10, 5, 0]
+            .iter()
+            .map(|shift| char::from(b'A' - 1 + ((id >> shift) & 0x1F) as u8))
+            .collect();
+
+        let mut edid = Self {
+            manufacturer,
+            product_code: u16::from_le_bytes([block[10], block[11]]),
+            serial_number: u32::from_le_bytes(block[12..16].try_into().unwrap()),
+            monitor_name: None,
+            serial: None,
+        };
+        for offset in DESCRIPTORS {
+            let descriptor = &block[offset..offset + 18];
+            // Detailed timing descriptors have a non zero pixel clock.
+            if descriptor[0..2] != [0, 0] {
+                continue;
+            }
+            match descriptor[3] {
+                0xFC => edid.monitor_name = descriptor_text(descriptor),
+                0xFF => edid.serial = descriptor_text(descriptor),
+                _ => {}
+            }
+        }
+
+        Ok(edid)
+    }
+
+    /// The model of the monitor, its name or else its product code.
+    pub fn model(&self) -> String {
+        self.monitor_name
+            .clone()
+            .unwrap_or_else(|| format!("0x{:04x}", self.product_code))
+    }
+
+    /// The serial of the monitor, the serial number descriptor or else the
+    /// numeric serial number, `None` if neither is set.
+    pub fn serial(&self) -> Option<String> {
+        self.serial
+            .clone()
+            .or_else(|| (self.serial_number != 0).then(|| self.serial_number.to_string()))
+    }
+
+    /// A device ID in the form used for displays, e.g.
+    /// `xrandr-DEL-DELL U2415-ABC123`, the serial is omitted if unknown.
+    ///
+    /// The PNP ID is used as it is, unlike in the IDs of the displays
+    /// registered by the session, e.g. `xrandr-Dell Inc.-DELL U2415-ABC123` by
+    /// gnome-settings-daemon, so the IDs of the same monitor do not match.
+    pub fn device_id(&self) -> String {
+        let mut id = format!("xrandr-{}-{}", self.manufacturer, self.model());
+        if let Some(serial) = self.serial() {
+            id.push('-');
+            id.push_str(&serial);
+        }
+        id
+    }
+}
+
+/// The text of a display descriptor, ended by a line feed and padded with
+/// spaces.
+fn descriptor_text(descriptor: &[u8]) -> Option<String> {
+    let text = &descriptor[5..18];
+    let text = text.split(|byte| *byte == b'\n').next().unwrap_or(text);
+    let text = String::from_utf8_lossy(text).trim().to_owned();
+
+    (!text.is_empty()).then_some(text)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A base block for `DEL`, product `0xa0c3` with the given descriptors.
+    fn block(serial_number: u32, name: Option<&str>, serial: Option<&str>) -> Vec<u8> {
+        let mut block = vec![0; BASE_BLOCK_SIZE];
+        block[..8].copy_from_slice(&HEADER);
+        // `D` = 4, `E` = 5, `L` = 12.
+        block[8..10].copy_from_slice(&((4u16 << 10) | (5 << 5) | 12).to_be_bytes());
+        block[10..12].copy_from_slice(&0xa0c3u16.to_le_bytes());
+        block[12..16].copy_from_slice(&serial_number.to_le_bytes());
+        // A detailed timing descriptor, which is skipped.
+        block[54] = 0x01;
+        for (offset, (tag, text)) in [(72, (0xFC, name)), (90, (0xFF, serial))] {
+            let Some(text) = text else { continue };
+            block[offset + 3] = tag;
+            let mut padded = format!("{text}\n").into_bytes();
+            padded.resize(13, b' ');
+            block[offset + 5..offset + 18].copy_from_slice(&padded);
+        }
+        let sum = block.iter().fold(0u8, |sum, byte| sum.wrapping_add(*byte));
+        block[127] = 0u8.wrapping_sub(sum);
+        block
+    }
+
+    #[test]
+    fn parse() {
+        let edid = Edid::parse(&block(0, Some("DELL U2415"), Some("ABC123"))).unwrap();
+        assert_eq!(edid.manufacturer, "DEL");
+        assert_eq!(edid.product_code, 0xa0c3);
+        assert_eq!(edid.monitor_name.as_deref(), Some("DELL U2415"));
+        assert_eq!(edid.serial().as_deref(), Some("ABC123"));
+        assert_eq!(edid.device_id(), "xrandr-DEL-DELL U2415-ABC123");
+
+        let edid = Edid::parse(&block(42, None, None)).unwrap();
+        assert_eq!(edid.model(), "0xa0c3");
+        assert_eq!(edid.serial().as_deref(), Some("42"));
+
+        let edid = Edid::parse(&block(0, None, None)).unwrap();
+        assert_eq!(edid.serial(), None);
+        assert_eq!(edid.device_id(), "xrandr-DEL-0xa0c3");
+    }
+
+    #[test]
+    fn invalid() {
+        let valid = block(0, Some("DELL U2415"), None);
+        assert!(Edid::parse(&valid[..64]).is_err());
+
+        let mut bad_header = valid.clone();
+        bad_header[0] = 0xFF;
+        assert!(Edid::parse(&bad_header).is_err());
+
+        let mut bad_checksum = valid;
+        bad_checksum[127] = bad_checksum[127].wrapping_add(1);
+        assert!(Edid::parse(&bad_checksum).is_err());
+    }
+}
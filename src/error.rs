@@ -0,0 +1,124 @@
+use std::{fmt, io};
+
+use crate::ParseDaemonVersionError;
+
+/// The error type for operations that can fail for reasons other than DBus.
+#[derive(Debug)]
+pub enum Error {
+    /// An error returned by the DBus connection or the daemon.
+    Zbus(zbus::Error),
+    /// An error reading a file on the client side.
+    Io(io::Error),
+    /// The file is not an ICC profile.
+    InvalidIcc(&'static str),
+    /// The data is not a valid EDID base block.
+    InvalidEdid(&'static str),
+    /// The daemon reported a version that cannot be parsed.
+    InvalidVersion(ParseDaemonVersionError),
+    /// There is no object with the given ID.
+    NotFound(String),
+    /// The caller is not allowed to perform the operation, e.g. deleting a
+    /// system-wide profile without administrator rights.
+    PermissionDenied(String),
+}
+
+/// A specialized [`Result`](std::result::Result) type for [`Error`].
+pub type Result<T> = std::result::Result<T, Error>;
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Zbus(err) => write!(f, "DBus error: {err}"),
+            Self::Io(err) => write!(f, "IO error: {err}"),
+            Self::InvalidIcc(reason) => write!(f, "Invalid ICC profile: {reason}"),
+            Self::InvalidEdid(reason) => write!(f, "Invalid EDID: {reason}"),
+            Self::InvalidVersion(err) => write!(f, "{err}"),
+            Self::NotFound(id) => write!(f, "No object with ID `{id}`"),
+            Self::PermissionDenied(message) => write!(f, "Permission denied: {message}"),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Zbus(err) => Some(err),
+            Self::Io(err) => Some(err),
+            Self::InvalidVersion(err) => Some(err),
+            Self::InvalidIcc(_)
+            | Self::InvalidEdid(_)
+            | Self::NotFound(_)
+            | Self::PermissionDenied(_) => None,
+        }
+    }
+}
+
+impl From<zbus::Error> for Error {
+    fn from(err: zbus::Error) -> Self {
+        Self::Zbus(err)
+    }
+}
+
+impl From<ParseDaemonVersionError> for Error {
+    fn from(err: ParseDaemonVersionError) -> Self {
+        Self::InvalidVersion(err)
+    }
+}
+
+impl From<io::Error> for Error {
+    fn from(err: io::Error) -> Self {
+        Self::Io(err)
+    }
+}
+
+/// The error returned by the daemon when an object cannot be found.
+pub(crate) const NOT_FOUND: &str = "org.freedesktop.ColorManager.NotFound";
+
+/// The errors returned by the daemon and the bus when the caller is not
+/// authorized.
+const PERMISSION_DENIED: &[&str] = &[
+    "org.freedesktop.ColorManager.FailedToAuthenticate",
+    "org.freedesktop.DBus.Error.AccessDenied",
+];
+
+/// Maps the DBus errors of a method acting on the object at `path` to
+/// [`Error::NotFound`] and [`Error::PermissionDenied`].
+pub(crate) fn discriminate(err: zbus::Error, path: &str) -> Error {
+    match err {
+        zbus::Error::MethodError(name, _, _) if name.as_str() == NOT_FOUND => {
+            Error::NotFound(path.to_owned())
+        }
+        zbus::Error::MethodError(name, message, _)
+            if PERMISSION_DENIED.contains(&name.as_str()) =>
+        {
+            Error::PermissionDenied(message.unwrap_or_default())
+        }
+        err => Error::Zbus(err),
+    }
+}
+
+/// The error returned when reading a property the object does not have.
+const UNKNOWN_PROPERTY: &str = "org.freedesktop.DBus.Error.UnknownProperty";
+
+/// Maps the string value of an optional property to `Ok(None)` if it is empty
+/// or if the object does not have the property, e.g. with older daemons.
+pub(crate) fn optional_property(result: zbus::Result<String>) -> zbus::Result<Option<String>> {
+    match result {
+        Ok(value) if value.is_empty() => Ok(None),
+        Ok(value) => Ok(Some(value)),
+        Err(zbus::Error::FDO(err)) if matches!(*err, zbus::fdo::Error::UnknownProperty(_)) => {
+            Ok(None)
+        }
+        Err(zbus::Error::MethodError(name, _, _)) if name.as_str() == UNKNOWN_PROPERTY => Ok(None),
+        Err(err) => Err(err),
+    }
+}
+
+/// Maps a DBus method error called `name` to `Ok(None)`.
+pub(crate) fn none_on_error<T>(result: zbus::Result<T>, name: &str) -> zbus::Result<Option<T>> {
+    match result {
+        Ok(value) => Ok(Some(value)),
+        Err(zbus::Error::MethodError(error_name, _, _)) if error_name.as_str() == name => Ok(None),
+        Err(err) => Err(err),
+    }
+}
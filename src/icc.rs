@@ -0,0 +1,116 @@
+//! Minimal ICC header checks, see the ICC.1 specification section 7.2.
+
+use crate::Error;
+
+/// The size of the ICC profile header in bytes.
+pub(crate) const HEADER_SIZE: usize = 128;
+
+/// Checks that `header` looks like the start of an ICC profile of
+/// `file_size` bytes.
+pub(crate) fn validate_header(header: &[u8], file_size: u64) -> Result<(), Error> {
+    if header.len() < HEADER_SIZE {
+        return Err(Error::InvalidIcc("File is smaller than an ICC header"));
+    }
+    if &header[36..40] != b"acsp" {
+        return Err(Error::InvalidIcc("Missing `acsp` signature"));
+    }
+    let profile_size = u32::from_be_bytes(header[0..4].try_into().unwrap()) as u64;
+    if profile_size < HEADER_SIZE as u64 || profile_size > file_size {
+        return Err(Error::InvalidIcc("Profile size in header is out of range"));
+    }
+
+    Ok(())
+}
+
+/// The MD5 checksum of `data` in lowercase hex, which colord uses for the
+/// IDs of the profiles it finds, e.g. `icc-<checksum>`.
+pub(crate) fn checksum(data: &[u8]) -> String {
+    // Per round shift amounts and the integer parts of `abs(sin(i + 1)) * 2^32`,
+    // see RFC 1321.
+    const S: [u32; 64] = [
+        7, 12, 17, 22, 7, 12, 17, 22, 7, 12, 17, 22, 7, 12, 17, 22, 5, 9, 14, 20, 5, 9, 14, 20, 5,
+        9, 14, 20, 5, 9, 14, 20, 4, 11, 16, 23, 4, 11, 16, 23, 4, 11, 16, 23, 4, 11, 16, 23, 6, 10,
+        15, 21, 6, 10, 15, 21, 6, 10, 15, 21, 6, 10, 15, 21,
+    ];
+    let k: Vec<u32> = (0..64)
+        .map(|i| ((i as f64 + 1.0).sin().abs() * 4_294_967_296.0) as u32)
+        .collect();
+
+    let mut message = data.to_vec();
+    message.push(0x80);
+    while message.len() % 64 != 56 {
+        message.push(0);
+    }
+    message.extend_from_slice(&((data.len() as u64).wrapping_mul(8)).to_le_bytes());
+
+    let mut state: [u32; 4] = [0x67452301, 0xefcdab89, 0x98badcfe, 0x10325476];
+    for chunk in message.chunks_exact(64) {
+        let m: Vec<u32> = chunk
+            .chunks_exact(4)
+            .map(|word| u32::from_le_bytes(word.try_into().unwrap()))
+            .collect();
+        let [mut a, mut b, mut c, mut d] = state;
+        for i in 0..64 {
+            let (f, g) = match i / 16 {
+                0 => ((b & c) | (!b & d), i),
+                1 => ((d & b) | (!d & c), (5 * i + 1) % 16),
+                2 => (b ^ c ^ d, (3 * i + 5) % 16),
+                _ => (c ^ (b | !d), (7 * i) % 16),
+            };
+            let f = f.wrapping_add(a).wrapping_add(k[i]).wrapping_add(m[g]);
+            a = d;
+            d = c;
+            c = b;
+            b = b.wrapping_add(f.rotate_left(S[i]));
+        }
+        for (state, value) in state.iter_mut().zip([a, b, c, d]) {
+            *state = state.wrapping_add(value);
+        }
+    }
+
+    state
+        .iter()
+        .flat_map(|word| word.to_le_bytes())
+        .map(|byte| format!("{byte:02x}"))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn md5_checksum() {
+        assert_eq!(checksum(b""), "d41d8cd98f00b204e9800998ecf8427e");
+        assert_eq!(checksum(b"abc"), "900150983cd24fb0d6963f7d28e17f72");
+        assert_eq!(
+            checksum(b"The quick brown fox jumps over the lazy dog"),
+            "9e107d9d372bb6826bd81d3542a419d6"
+        );
+        // Longer than a single block.
+        assert_eq!(checksum(&[b'a'; 1000]), "cabe45dcc9ae5b66ba86600cca6b8ba8");
+    }
+
+    fn header(size: u32) -> Vec<u8> {
+        let mut header = vec![0; HEADER_SIZE];
+        header[0..4].copy_from_slice(&size.to_be_bytes());
+        header[36..40].copy_from_slice(b"acsp");
+        header
+    }
+
+    #[test]
+    fn valid_header() {
+        assert!(validate_header(&header(512), 512).is_ok());
+    }
+
+    #[test]
+    fn invalid_header() {
+        assert!(validate_header(&header(512)[..64], 512).is_err());
+        assert!(validate_header(&header(1024), 512).is_err());
+        assert!(validate_header(&header(64), 512).is_err());
+
+        let mut bad_magic = header(512);
+        bad_magic[36..40].copy_from_slice(b"abcd");
+        assert!(validate_header(&bad_magic, 512).is_err());
+    }
+}
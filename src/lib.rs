@@ -1,23 +1,128 @@
 #![deny(rustdoc::broken_intra_doc_links)]
 #![doc = include_str!("../README.md")]
-#![cfg_attr(feature = "docs", feature(doc_auto_cfg))]
+#![cfg_attr(docsrs, feature(doc_cfg))]
 
+#[cfg(feature = "blocking")]
+pub mod blocking;
+pub mod cache;
+pub mod collections;
 mod color_manager;
+mod config;
 pub mod device;
-mod profile;
+pub mod edid;
+mod error;
+mod icc;
+mod owner;
+pub mod profile;
+mod property;
+pub mod qualifier;
 mod scope;
-mod sensor;
+pub mod sensor;
+mod timeout;
+mod trace;
+#[cfg(feature = "users")]
+mod users;
+mod version;
+mod wire;
+pub mod workflow;
 
-pub use color_manager::ColorManager;
+pub use color_manager::{
+    Change, ChangeSubscription, ColorManager, ColorManagerBuilder, SystemInfo,
+};
+pub use config::{BusKind, ClientConfig};
 pub use device::Device;
+pub use error::{Error, Result};
+pub use owner::Owner;
 pub use profile::Profile;
-pub use scope::Scope;
+pub use property::PropertyValue;
+pub use scope::{ParseScopeError, Scope};
 pub use sensor::Sensor;
+pub use version::{DaemonVersion, ParseDaemonVersionError};
 
 #[cfg(test)]
 mod tests {
+    use byteorder::LE;
+    use serde::{de::DeserializeOwned, Serialize};
+    use zbus::zvariant::{self, EncodingContext, Type};
+
     use super::*;
 
     #[test]
     fn it_works() {}
+
+    fn assert_wire<T>(value: T, expected: &str)
+    where
+        T: Serialize + DeserializeOwned + Type + PartialEq + std::fmt::Debug,
+    {
+        assert_eq!(T::signature(), "s");
+
+        let ctxt = EncodingContext::<LE>::new_dbus(0);
+        let bytes = zvariant::to_bytes(ctxt, &value).unwrap();
+        assert_eq!(
+            zvariant::from_slice::<_, String>(&bytes, ctxt).unwrap(),
+            expected
+        );
+        assert_eq!(zvariant::from_slice::<_, T>(&bytes, ctxt).unwrap(), value);
+    }
+
+    #[test]
+    fn wire_enums() {
+        assert_wire(Scope::Normal, "normal");
+        assert_wire(Scope::Temp, "temp");
+        assert_wire(Scope::Disk, "disk");
+        for scope in [Scope::Normal, Scope::Temp, Scope::Disk] {
+            assert_wire(scope.clone(), scope.as_wire_str());
+        }
+
+        assert_wire(device::Mode::Virtual, "virtual");
+        assert_wire(device::Mode::Physical, "physical");
+        assert_wire(device::Mode::Unknown, "unknown");
+
+        assert_wire(device::Kind::Scanner, "scanner");
+        assert_wire(device::Kind::Display, "display");
+        assert_wire(device::Kind::Camera, "camera");
+        assert_wire(device::Kind::Printer, "printer");
+        assert_wire(device::Kind::Webcam, "webcam");
+        for kind in [
+            device::Kind::Scanner,
+            device::Kind::Display,
+            device::Kind::Camera,
+            device::Kind::Printer,
+            device::Kind::Webcam,
+        ] {
+            assert_wire(kind, kind.as_str());
+        }
+
+        assert_wire(device::Relation::Soft, "soft");
+        assert_wire(device::Relation::Hard, "hard");
+
+        assert_wire(sensor::Mode::Ambient, "ambient");
+        assert_wire(sensor::Mode::Printer, "printer");
+        assert_wire(sensor::Mode::Unknown, "unknown");
+
+        assert_wire(sensor::State::Starting, "starting");
+        assert_wire(sensor::State::Idle, "idle");
+        assert_wire(sensor::State::Measuring, "measuring");
+        assert_wire(sensor::State::Busy, "busy");
+        assert_wire(sensor::State::Unknown, "unknown");
+
+        assert_wire(sensor::Capability::Crt, "crt");
+        assert_wire(sensor::Capability::Ambient, "ambient");
+        assert_wire(sensor::Capability::Lcd, "lcd");
+        assert_wire(sensor::Capability::Led, "led");
+        assert_wire(sensor::Capability::Projector, "projector");
+        assert_wire(sensor::Capability::Printer, "printer");
+        assert_wire(sensor::Capability::Spot, "spot");
+        assert_wire(sensor::Capability::Calibration, "calibration");
+        assert_wire(sensor::Capability::Plasma, "plasma");
+        assert_wire(sensor::Capability::LcdCcfl, "lcd-ccfl");
+        assert_wire(sensor::Capability::LcdRgbLed, "lcd-rgb-led");
+        assert_wire(sensor::Capability::LcdWhiteLed, "lcd-white-led");
+        assert_wire(sensor::Capability::WideGamutLcdCcfl, "wide-gamut-lcd-ccfl");
+        assert_wire(
+            sensor::Capability::WideGamutLcdRgbLed,
+            "wide-gamut-lcd-rgb-led",
+        );
+        assert_wire(sensor::Capability::Unknown, "unknown");
+    }
 }
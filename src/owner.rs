@@ -0,0 +1,67 @@
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+use zbus::zvariant::{self, Type};
+
+/// The account that created a device or profile, see
+/// [`Device::owner`](crate::Device::owner) and
+/// [`Profile::owner`](crate::Profile::owner).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, Type)]
+pub struct Owner(pub u32);
+
+impl Owner {
+    /// The user ID of the account.
+    pub fn uid(&self) -> u32 {
+        self.0
+    }
+
+    #[cfg(feature = "users")]
+    /// The name of the account, `None` if the user ID cannot be resolved.
+    pub fn name(&self) -> Option<String> {
+        crate::users::user_name(self.0)
+    }
+}
+
+/// The real user ID of the current process.
+pub(crate) fn current_uid() -> u32 {
+    // SAFETY: getuid has no preconditions and always succeeds.
+    unsafe { libc::getuid() }
+}
+
+impl TryFrom<zvariant::OwnedValue> for Owner {
+    type Error = zvariant::Error;
+
+    fn try_from(value: zvariant::OwnedValue) -> Result<Self, Self::Error> {
+        u32::try_from(value).map(Self)
+    }
+}
+
+impl From<u32> for Owner {
+    fn from(uid: u32) -> Self {
+        Self(uid)
+    }
+}
+
+impl fmt::Display for Owner {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use zvariant::{OwnedValue, Value};
+
+    use super::*;
+
+    #[test]
+    fn owner_from_owned_value() {
+        assert_eq!(Owner::signature(), "u");
+
+        let owner = Owner::try_from(OwnedValue::from(Value::from(1000u32))).unwrap();
+        assert_eq!(owner.uid(), 1000);
+        assert_eq!(owner.to_string(), "1000");
+
+        assert!(Owner::try_from(OwnedValue::from(Value::from("1000"))).is_err());
+    }
+}
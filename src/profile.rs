@@ -1,19 +1,233 @@
-use std::collections::HashMap;
+use std::{
+    collections::HashMap,
+    hash::{Hash, Hasher},
+    path::PathBuf,
+    sync::Arc,
+    time::Duration,
+};
 
-use futures_util::StreamExt;
+use futures_util::{
+    future::{try_join, try_join3},
+    StreamExt,
+};
 use serde::Serialize;
 use zbus::{
-    zvariant::{ObjectPath, Type},
+    zvariant::{ObjectPath, OwnedObjectPath, OwnedValue, Type},
     Result,
 };
 
-use crate::Scope;
+use crate::{
+    config::PropertyCache, qualifier::Format, trace::ProxyExt, wire, ClientConfig, Device, Owner,
+    PropertyValue, Scope,
+};
+
+/// The keys that can be used to find a profile by property.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ProfileProperty {
+    Qualifier,
+    Colorspace,
+    Kind,
+    Filename,
+    /// A metadata key, e.g. `EDID_md5`.
+    Metadata(String),
+}
+
+impl ProfileProperty {
+    /// The key used on the wire, e.g. `Qualifier`.
+    pub fn as_str(&self) -> &str {
+        match self {
+            Self::Qualifier => "Qualifier",
+            Self::Colorspace => "Colorspace",
+            Self::Kind => "Kind",
+            Self::Filename => "Filename",
+            Self::Metadata(key) => key,
+        }
+    }
+}
+
+/// A standard color space a system wide profile can define itself as, see
+/// [`ColorManager::standard_space_typed`](crate::ColorManager::standard_space_typed).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StandardSpace {
+    Srgb,
+    AdobeRgb,
+    ProphotoRgb,
+}
+
+impl StandardSpace {
+    /// The value of the `STANDARD_space` metadata entry, e.g. `adobe-rgb`.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Srgb => "srgb",
+            Self::AdobeRgb => "adobe-rgb",
+            Self::ProphotoRgb => "prophoto-rgb",
+        }
+    }
+}
+
+/// A problem detected by the daemon in a profile, see
+/// [`Profile::warnings_typed`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Warning {
+    DescriptionMissing,
+    CopyrightMissing,
+    VcgtNonMonotonic,
+    ScumDot,
+    GrayAxisInvalid,
+    GrayAxisNonMonotonic,
+    PrimariesInvalid,
+    PrimariesNonAdditive,
+    PrimariesUnlikely,
+    WhitepointInvalid,
+    WhitepointUnlikely,
+    /// A warning not known to this crate.
+    Other(String),
+}
+
+impl Warning {
+    /// The string used for the warning on the wire, e.g.
+    /// `description-missing`.
+    pub fn as_str(&self) -> &str {
+        match self {
+            Self::DescriptionMissing => "description-missing",
+            Self::CopyrightMissing => "copyright-missing",
+            Self::VcgtNonMonotonic => "vcgt-non-monotonic",
+            Self::ScumDot => "scum-dot",
+            Self::GrayAxisInvalid => "gray-axis-invalid",
+            Self::GrayAxisNonMonotonic => "gray-axis-non-monotonic",
+            Self::PrimariesInvalid => "primaries-invalid",
+            Self::PrimariesNonAdditive => "primaries-non-additive",
+            Self::PrimariesUnlikely => "primaries-unlikely",
+            Self::WhitepointInvalid => "whitepoint-invalid",
+            Self::WhitepointUnlikely => "whitepoint-unlikely",
+            Self::Other(warning) => warning,
+        }
+    }
+
+    /// A short human readable description of the warning.
+    pub fn description(&self) -> &str {
+        match self {
+            Self::DescriptionMissing => "The profile has no description",
+            Self::CopyrightMissing => "The profile has no copyright",
+            Self::VcgtNonMonotonic => "The video card gamma table is not monotonic",
+            Self::ScumDot => "The profile has an unlikely black point",
+            Self::GrayAxisInvalid => "The gray axis contains significant amounts of color",
+            Self::GrayAxisNonMonotonic => "The gray axis is not monotonic",
+            Self::PrimariesInvalid => "One or more of the primaries are invalid",
+            Self::PrimariesNonAdditive => "The primaries do not add up to white",
+            Self::PrimariesUnlikely => "One or more of the primaries are unlikely",
+            Self::WhitepointInvalid => "The white point is invalid",
+            Self::WhitepointUnlikely => "The white point temperature is unlikely",
+            Self::Other(warning) => warning,
+        }
+    }
+}
+
+/// A problem found in a profile by [`Profile::validate`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ValidationIssue {
+    /// A warning of the daemon, see [`Profile::warnings_typed`].
+    Daemon(Warning),
+    /// The colorspace is unusual for the kind of the profile, e.g. a `cmyk`
+    /// display profile. The daemon does not check this.
+    ColorspaceMismatch { kind: String, colorspace: String },
+}
+
+impl ValidationIssue {
+    /// A short human readable description of the issue.
+    pub fn description(&self) -> &str {
+        match self {
+            Self::Daemon(warning) => warning.description(),
+            Self::ColorspaceMismatch { .. } => "The colorspace is unusual for the kind of profile",
+        }
+    }
+}
+
+/// The colorspaces expected for a profile `kind`, `None` if any colorspace is
+/// fine.
+///
+/// This is a heuristic, the ICC specification allows LUT based profiles of
+/// any device class in any colorspace. Display and input profiles usually
+/// describe `rgb` or `gray` devices, output profiles also printers using `cmy`
+/// or `cmyk`. The `unknown` colorspace, which colord reports for colorspaces
+/// it has no name for, e.g. of printers with more inks, is always accepted.
+fn expected_colorspaces(kind: &str) -> Option<&'static [&'static str]> {
+    match kind {
+        "display-device" => Some(&["rgb", "gray", "unknown"]),
+        "input-device" => Some(&["rgb", "gray", "unknown"]),
+        "output-device" => Some(&["rgb", "cmy", "cmyk", "gray", "unknown"]),
+        _ => None,
+    }
+}
+
+impl From<&str> for Warning {
+    fn from(value: &str) -> Self {
+        match value {
+            "description-missing" => Self::DescriptionMissing,
+            "copyright-missing" => Self::CopyrightMissing,
+            "vcgt-non-monotonic" => Self::VcgtNonMonotonic,
+            "scum-dot" => Self::ScumDot,
+            "gray-axis-invalid" => Self::GrayAxisInvalid,
+            "gray-axis-non-monotonic" => Self::GrayAxisNonMonotonic,
+            "primaries-invalid" => Self::PrimariesInvalid,
+            "primaries-non-additive" => Self::PrimariesNonAdditive,
+            "primaries-unlikely" => Self::PrimariesUnlikely,
+            "whitepoint-invalid" => Self::WhitepointInvalid,
+            "whitepoint-unlikely" => Self::WhitepointUnlikely,
+            other => Self::Other(other.to_owned()),
+        }
+    }
+}
+
+/// Metadata key for the kind of data the profile was created from, see
+/// [`Profile::data_source`].
+pub const METADATA_DATA_SOURCE: &str = "DATA_source";
+
+/// The kind of data a profile was created from, see [`Profile::data_source`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DataSource {
+    /// Created out of measurements taken with a sensor.
+    Calib,
+    /// Generated from the EDID of a display.
+    Edid,
+    /// A standard color space such as sRGB.
+    Standard,
+    /// A profile used for testing.
+    Test,
+    /// A data source not known to this crate.
+    Other(String),
+}
+
+impl DataSource {
+    /// The value of the `DATA_source` metadata entry, e.g. `edid`.
+    pub fn as_str(&self) -> &str {
+        match self {
+            Self::Calib => "calib",
+            Self::Edid => "edid",
+            Self::Standard => "standard",
+            Self::Test => "test",
+            Self::Other(source) => source,
+        }
+    }
+}
 
-#[derive(Type, Debug)]
+impl From<&str> for DataSource {
+    fn from(value: &str) -> Self {
+        match value {
+            "calib" => Self::Calib,
+            "edid" => Self::Edid,
+            "standard" => Self::Standard,
+            "test" => Self::Test,
+            other => Self::Other(other.to_owned()),
+        }
+    }
+}
+
+#[derive(Type, Clone)]
 #[zvariant(signature = "o")]
 #[doc(alias = "org.freedesktop.ColorManager.Profile")]
 /// A wrapper of the `org.freedesktop.ColorManager.Profile` DBus object.
-pub struct Profile<'a>(zbus::Proxy<'a>);
+pub struct Profile<'a>(zbus::Proxy<'a>, Arc<ClientConfig>, PropertyCache);
 
 impl<'a> Profile<'a> {
     pub async fn new<P>(connection: &zbus::Connection, object_path: P) -> Result<Profile<'a>>
@@ -21,19 +235,32 @@ impl<'a> Profile<'a> {
         P: TryInto<ObjectPath<'a>>,
         P::Error: Into<zbus::Error>,
     {
-        let inner = zbus::ProxyBuilder::new_bare(connection)
-            .interface("org.freedesktop.ColorManager.Profile")?
-            .path(object_path)?
-            .destination("org.freedesktop.ColorManager")?
-            .cache_properties(zbus::CacheProperties::No)
-            .build()
+        Self::with_config(connection, object_path, Arc::default()).await
+    }
+
+    pub(crate) async fn with_config<P>(
+        connection: &zbus::Connection,
+        object_path: P,
+        config: Arc<ClientConfig>,
+    ) -> Result<Profile<'a>>
+    where
+        P: TryInto<ObjectPath<'a>>,
+        P::Error: Into<zbus::Error>,
+    {
+        let inner = config
+            .proxy(
+                connection,
+                "org.freedesktop.ColorManager.Profile",
+                object_path,
+            )
             .await?;
-        Ok(Self(inner))
+        Ok(Self(inner, config, PropertyCache::default()))
     }
 
     pub(crate) async fn from_paths<P>(
         connection: &zbus::Connection,
         paths: Vec<P>,
+        config: Arc<ClientConfig>,
     ) -> Result<Vec<Profile<'a>>>
     where
         P: TryInto<ObjectPath<'a>>,
@@ -41,36 +268,94 @@ impl<'a> Profile<'a> {
     {
         let mut items = Vec::with_capacity(paths.capacity());
         for path in paths.into_iter() {
-            items.push(Self::new(connection, path).await?);
+            items.push(Self::with_config(connection, path, config.clone()).await?);
         }
         Ok(items)
     }
 
-    pub fn inner(&self) -> &zbus::Proxy {
+    pub fn inner(&self) -> &zbus::Proxy<'_> {
         &self.0
     }
 
+    /// The settings this object was created with.
+    pub fn config(&self) -> &ClientConfig {
+        &self.1
+    }
+
+    pub(crate) fn shared_config(&self) -> Arc<ClientConfig> {
+        self.1.clone()
+    }
+
+    /// Drops the property values cached with
+    /// [`ClientConfig::cache_properties`], the next reads get them from the
+    /// daemon again. Does nothing if the properties are not cached.
+    ///
+    /// Clones of the profile are refreshed too.
+    pub async fn refresh(&self) -> Result<()> {
+        self.2
+            .refresh(&self.1, &self.0, "org.freedesktop.ColorManager.Profile")
+            .await
+    }
+
     #[doc(alias = "SetProperty")]
     /// Sets a property on the object.
     pub async fn set_property(&self, property_name: &str, property_value: &str) -> Result<()> {
-        self.inner()
-            .call_method("SetProperty", &(property_name, property_value))
+        self.traced_call("SetProperty", &(property_name, property_value))
             .await?;
 
         Ok(())
     }
 
+    #[doc(alias = "SetProperty")]
+    /// Like [`Profile::set_property`] but converts a typed value to the string
+    /// expected by the daemon.
+    ///
+    /// `Qualifier`, `Format`, `Filename`, `Colorspace`, `Kind`, `Title` and
+    /// metadata keys all take strings.
+    pub async fn set_property_value<V>(&self, property_name: &str, property_value: V) -> Result<()>
+    where
+        V: PropertyValue,
+    {
+        self.set_property(property_name, &property_value.to_property_string())
+            .await
+    }
+
+    #[doc(alias = "SetProperty")]
+    /// Sets the qualifier used to match the profile to a device, e.g.
+    /// `RGB.Plain.300dpi`.
+    pub async fn set_qualifier(&self, qualifier: &str) -> Result<()> {
+        self.set_property(ProfileProperty::Qualifier.as_str(), qualifier)
+            .await
+    }
+
     #[doc(alias = "InstallSystemWide")]
     /// Copies the profile system-wide so it can be used by all users on the
     /// system or when no users are logged-in.
     pub async fn install_system_wide(&self) -> Result<()> {
-        self.inner().call_method("InstallSystemWide", &()).await?;
+        self.traced_call("InstallSystemWide", &()).await?;
 
         Ok(())
     }
 
+    #[doc(alias = "InstallSystemWide")]
+    /// Like [`Profile::install_system_wide`] but returns the filename of the
+    /// system-wide copy of the profile.
+    pub async fn install_system_wide_and_get_path(&self) -> Result<PathBuf> {
+        self.install_system_wide().await?;
+        if !self.is_system_wide().await? {
+            return Err(zbus::Error::Failure(
+                "Profile was not installed system wide".into(),
+            ));
+        }
+
+        self.filename().await.map(PathBuf::from)
+    }
+
     #[doc(alias = "Changed")]
     /// Some value on the interface has changed.
+    ///
+    /// The signal has no arguments, re-read the properties of the profile to
+    /// know what changed.
     pub async fn changed(&self) -> Result<()> {
         let mut stream = self.inner().receive_signal("Changed").await?;
         stream
@@ -84,21 +369,91 @@ impl<'a> Profile<'a> {
     #[doc(alias = "ProfileId")]
     /// The identification hash of the profile.
     pub async fn profile_id(&self) -> Result<String> {
-        self.inner().get_property("ProfileId").await
+        self.traced_property("ProfileId").await
+    }
+
+    #[doc(alias = "ProfileId")]
+    /// If both profiles have the same identification hash, i.e. they refer to
+    /// the same ICC data even if their object paths differ, e.g. a user and a
+    /// system wide copy of a profile.
+    pub async fn same_profile_as(&self, other: &Profile<'_>) -> Result<bool> {
+        let (id, other_id) = try_join(self.profile_id(), other.profile_id()).await?;
+
+        Ok(id == other_id)
+    }
+
+    #[doc(alias = "Profiles")]
+    /// If the profile is the default profile of `device`, i.e. the first one
+    /// in [`Device::profiles`](crate::Device::profiles).
+    ///
+    /// Profiles are compared by object path, see
+    /// [`Device::make_profile_default`](crate::Device::make_profile_default)
+    /// to change the default.
+    pub async fn is_default_for(&self, device: &Device<'_>) -> Result<bool> {
+        let profiles = device.profiles().await?;
+
+        Ok(profiles.first().is_some_and(|default| default == self))
+    }
+
+    #[doc(alias = "Warnings")]
+    /// The warnings of the daemon about the profile, followed by the problems
+    /// found by this crate, e.g. [`ValidationIssue::ColorspaceMismatch`] when
+    /// the colorspace does not suit the kind of the profile.
+    ///
+    /// An empty list means the profile is fine for its kind.
+    pub async fn validate(&self) -> Result<Vec<ValidationIssue>> {
+        let (kind, colorspace, warnings) =
+            try_join3(self.kind(), self.colorspace(), self.warnings_typed()).await?;
+
+        let mut issues: Vec<_> = warnings.into_iter().map(ValidationIssue::Daemon).collect();
+        let mismatch = expected_colorspaces(&kind)
+            .is_some_and(|expected| !expected.contains(&colorspace.as_str()));
+        if mismatch {
+            issues.push(ValidationIssue::ColorspaceMismatch { kind, colorspace });
+        }
+
+        Ok(issues)
     }
 
     #[doc(alias = "Title")]
     /// The printable title for the profile.
     pub async fn title(&self) -> Result<String> {
-        self.inner().get_property("Title").await
+        self.traced_property("Title").await
     }
 
     #[doc(alias = "Metadata")]
     /// The metadata for the profile, which may include optional keys like
     /// `EDID_md5` and `EDID_manufacturer` that are set by several CMS
     /// frameworks.
+    ///
+    /// Entries whose value is not a string are skipped, see
+    /// [`Profile::metadata_raw`].
     pub async fn metadata(&self) -> Result<HashMap<String, String>> {
-        self.inner().get_property("Metadata").await
+        Ok(wire::string_metadata(self.metadata_raw().await?))
+    }
+
+    #[cfg(feature = "serde_json")]
+    #[doc(alias = "Metadata")]
+    /// The metadata for the profile as a JSON object, see [`Profile::metadata`].
+    pub async fn metadata_json(&self) -> Result<serde_json::Value> {
+        Ok(wire::metadata_json(self.metadata().await?))
+    }
+
+    #[doc(alias = "Metadata")]
+    /// The metadata for the profile with the values as sent by the daemon.
+    pub async fn metadata_raw(&self) -> Result<HashMap<String, OwnedValue>> {
+        self.traced_property("Metadata").await
+    }
+
+    #[doc(alias = "Metadata")]
+    /// The kind of data the profile was created from, read from the
+    /// `DATA_source` metadata entry. `None` if the entry is not set.
+    pub async fn data_source(&self) -> Result<Option<DataSource>> {
+        let metadata = self.metadata().await?;
+
+        Ok(metadata
+            .get(METADATA_DATA_SOURCE)
+            .map(|source| DataSource::from(source.as_str())))
     }
 
     #[doc(alias = "Qualifier")]
@@ -108,13 +463,19 @@ impl<'a> Profile<'a> {
     /// might be something free text like `High quality studio` or something
     /// more programmable like `RGB.Plain.300dpi`.
     pub async fn qualifier(&self) -> Result<String> {
-        self.inner().get_property("Qualifier").await
+        self.traced_property("Qualifier").await
     }
 
     #[doc(alias = "Format")]
     /// The qualifier format for the profile.
     pub async fn format(&self) -> Result<String> {
-        self.inner().get_property("Format").await
+        self.traced_property("Format").await
+    }
+
+    #[doc(alias = "Format")]
+    /// Like [`Profile::format`] but split into its components.
+    pub async fn format_parsed(&self) -> Result<Format> {
+        self.format().await.map(Format::from)
     }
 
     // TODO Use enum.
@@ -122,32 +483,32 @@ impl<'a> Profile<'a> {
     /// The profile kind, e.g. `colorspace-conversion`, `abstract` or
     /// `display-device`.
     pub async fn kind(&self) -> Result<String> {
-        self.inner().get_property("Kind").await
+        self.traced_property("Kind").await
     }
 
     #[doc(alias = "Colorspace")]
     /// The profile colorspace, e.g. `rgb`.
     pub async fn colorspace(&self) -> Result<String> {
-        self.inner().get_property("Colorspace").await
+        self.traced_property("Colorspace").await
     }
 
     #[doc(alias = "HasVcgt")]
     /// If the profile has a VCGT entry.
     pub async fn has_vcgt(&self) -> Result<bool> {
-        self.inner().get_property("HasVcgt").await
+        self.traced_property("HasVcgt").await
     }
 
     #[doc(alias = "IsSystemWide")]
     /// If the profile is installed system wide and available for all users.
     pub async fn is_system_wide(&self) -> Result<bool> {
-        self.inner().get_property("IsSystemWide").await
+        self.traced_property("IsSystemWide").await
     }
 
     // TODO Use Path or something.
     #[doc(alias = "Filename")]
     /// The profile filename, if one exists.
     pub async fn filename(&self) -> Result<String> {
-        self.inner().get_property("Filename").await
+        self.traced_property("Filename").await
     }
 
     #[doc(alias = "Created")]
@@ -157,19 +518,27 @@ impl<'a> Profile<'a> {
     /// colord, nor the disk timestamp for the profile filename. This is the
     /// encoded date and time inside the ICC filename.
     pub async fn created(&self) -> Result<u64> {
-        self.inner().get_property("Created").await
+        self.traced_property("Created").await
     }
 
     #[doc(alias = "Scope")]
     /// The scope of the device, e.g. `normal`, `temp` or `disk`.
     pub async fn scope(&self) -> Result<Scope> {
-        self.inner().get_property("Scope").await
+        self.traced_property("Scope").await
     }
 
     #[doc(alias = "Owner")]
     /// The user ID of the account that created the profile.
-    pub async fn owner(&self) -> Result<u32> {
-        self.inner().get_property("Owner").await
+    pub async fn owner(&self) -> Result<Owner> {
+        self.traced_property::<Owner>("Owner").await
+    }
+
+    #[cfg(feature = "users")]
+    #[doc(alias = "Owner")]
+    /// The name of the account that created the profile, `None` if the user ID
+    /// cannot be resolved.
+    pub async fn owner_name(&self) -> Result<Option<String>> {
+        Ok(self.owner().await?.name())
     }
 
     #[doc(alias = "Warnings")]
@@ -177,7 +546,38 @@ impl<'a> Profile<'a> {
     ///
     /// e.g. 'description-missing' or 'vcgt-non-monotonic'.
     pub async fn warnings(&self) -> Result<Vec<String>> {
-        self.inner().get_property("Warnings").await
+        self.traced_property("Warnings").await
+    }
+
+    #[doc(alias = "Warnings")]
+    /// Like [`Profile::warnings`] but mapped to [`Warning`].
+    pub async fn warnings_typed(&self) -> Result<Vec<Warning>> {
+        let warnings = self.warnings().await?;
+
+        Ok(warnings
+            .iter()
+            .map(|warning| Warning::from(warning.as_str()))
+            .collect())
+    }
+}
+
+impl ProxyExt for Profile<'_> {
+    fn proxy(&self) -> &zbus::Proxy<'_> {
+        &self.0
+    }
+
+    fn method_timeout(&self) -> Option<Duration> {
+        self.1.method_timeout
+    }
+
+    fn property_proxy(&self) -> Option<zbus::Proxy<'static>> {
+        self.2.proxy()
+    }
+}
+
+impl From<&Profile<'_>> for OwnedObjectPath {
+    fn from(profile: &Profile<'_>) -> Self {
+        profile.inner().path().to_owned().into()
     }
 }
 
@@ -189,3 +589,78 @@ impl<'a> Serialize for Profile<'a> {
         ObjectPath::serialize(self.inner().path(), serializer)
     }
 }
+
+/// Profiles are compared by their object path, see
+/// [`Profile::same_profile_as`] to compare the ICC data.
+impl PartialEq for Profile<'_> {
+    fn eq(&self, other: &Self) -> bool {
+        self.inner().path() == other.inner().path()
+    }
+}
+
+impl Eq for Profile<'_> {}
+
+impl Hash for Profile<'_> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.inner().path().hash(state);
+    }
+}
+
+/// Only the object path is printed, e.g.
+/// `Profile("/org/freedesktop/ColorManager/...")`.
+impl std::fmt::Debug for Profile<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_tuple("Profile")
+            .field(&self.inner().path().as_str())
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn warning_round_trip() {
+        for warning in ["description-missing", "scum-dot", "whitepoint-unlikely"] {
+            assert_eq!(Warning::from(warning).as_str(), warning);
+        }
+        assert_eq!(
+            Warning::from("vcgt-non-monotonic"),
+            Warning::VcgtNonMonotonic
+        );
+        assert_eq!(
+            Warning::from("something-new"),
+            Warning::Other("something-new".to_owned())
+        );
+    }
+
+    #[test]
+    fn expected_colorspace() {
+        let display = expected_colorspaces("display-device").unwrap();
+        assert!(display.contains(&"rgb"));
+        assert!(!display.contains(&"cmyk"));
+        let output = expected_colorspaces("output-device").unwrap();
+        assert!(output.contains(&"cmy"));
+        assert!(output.contains(&"unknown"));
+        assert!(!output.contains(&"lab"));
+        assert_eq!(expected_colorspaces("abstract"), None);
+        // Only the daemon reports warnings.
+        assert_eq!(
+            Warning::from("colorspace-mismatch"),
+            Warning::Other("colorspace-mismatch".to_owned())
+        );
+    }
+
+    #[test]
+    fn data_source_round_trip() {
+        for source in ["calib", "edid", "standard", "test", "vendor"] {
+            assert_eq!(DataSource::from(source).as_str(), source);
+        }
+        assert_eq!(DataSource::from("edid"), DataSource::Edid);
+        assert_eq!(
+            DataSource::from("vendor"),
+            DataSource::Other("vendor".to_owned())
+        );
+    }
+}
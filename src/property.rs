@@ -0,0 +1,67 @@
+use std::borrow::Cow;
+
+use crate::device;
+
+/// A value that can be given to `set_property_value`, see
+/// [`Device::set_property_value`](crate::Device::set_property_value) and
+/// [`Profile::set_property_value`](crate::Profile::set_property_value).
+///
+/// The daemon receives every property as a string, this converts typed values
+/// to the format it expects, e.g. [`device::Kind::Display`] becomes
+/// `display`.
+pub trait PropertyValue {
+    /// The string sent to the daemon for the value.
+    fn to_property_string(&self) -> Cow<'_, str>;
+}
+
+impl PropertyValue for str {
+    fn to_property_string(&self) -> Cow<'_, str> {
+        Cow::Borrowed(self)
+    }
+}
+
+impl PropertyValue for String {
+    fn to_property_string(&self) -> Cow<'_, str> {
+        Cow::Borrowed(self)
+    }
+}
+
+impl<T: PropertyValue + ?Sized> PropertyValue for &T {
+    fn to_property_string(&self) -> Cow<'_, str> {
+        (**self).to_property_string()
+    }
+}
+
+/// Booleans are sent as `true` or `false`.
+impl PropertyValue for bool {
+    fn to_property_string(&self) -> Cow<'_, str> {
+        Cow::Borrowed(if *self { "true" } else { "false" })
+    }
+}
+
+impl PropertyValue for device::Kind {
+    fn to_property_string(&self) -> Cow<'_, str> {
+        Cow::Borrowed(self.as_str())
+    }
+}
+
+impl PropertyValue for device::Mode {
+    fn to_property_string(&self) -> Cow<'_, str> {
+        Cow::Borrowed(self.as_str())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn property_strings() {
+        assert_eq!("Foo".to_property_string(), "Foo");
+        assert_eq!(String::from("Foo").to_property_string(), "Foo");
+        assert_eq!(true.to_property_string(), "true");
+        assert_eq!(false.to_property_string(), "false");
+        assert_eq!(device::Kind::Printer.to_property_string(), "printer");
+        assert_eq!(device::Mode::Virtual.to_property_string(), "virtual");
+    }
+}
@@ -0,0 +1,314 @@
+//! Profile qualifiers, e.g. `RGB.Plain.300dpi`, and the formats describing them.
+//!
+//! A qualifier is made of three dot separated components, usually the color
+//! model, the output mode and the resolution.
+
+/// The layout of the qualifiers of a device or profile, e.g.
+/// `ColorModel.OutputMode.OutputResolution`, see
+/// [`Device::format_parsed`](crate::Device::format_parsed).
+///
+/// Formats with fewer or more components than the usual three are kept as
+/// they are, the accessors of missing components return `None`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Format(String);
+
+impl Format {
+    /// The format as received from the daemon.
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    /// The dot separated components of the format, empty if the format is.
+    pub fn components(&self) -> Vec<&str> {
+        if self.0.is_empty() {
+            return Vec::new();
+        }
+        self.0.split('.').collect()
+    }
+
+    /// The name of the first component, usually `ColorModel`.
+    pub fn color_model(&self) -> Option<&str> {
+        self.components().first().copied()
+    }
+
+    /// The name of the second component, usually `OutputMode`.
+    pub fn output_mode(&self) -> Option<&str> {
+        self.components().get(1).copied()
+    }
+
+    /// The name of the third component, usually `OutputResolution`.
+    pub fn output_resolution(&self) -> Option<&str> {
+        self.components().get(2).copied()
+    }
+}
+
+impl From<String> for Format {
+    fn from(value: String) -> Self {
+        Self(value)
+    }
+}
+
+impl std::fmt::Display for Format {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+/// A component of a [`QualifierQuery`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Component {
+    /// Matches only this exact value, e.g. `RGB`.
+    Exact(String),
+    /// Matches any value, sent to the daemon as `*`.
+    Any,
+}
+
+impl Component {
+    /// The component as sent to the daemon.
+    pub fn as_str(&self) -> &str {
+        match self {
+            Self::Exact(value) => value,
+            Self::Any => "*",
+        }
+    }
+}
+
+/// A qualifier to look up a profile with, made of exact components and
+/// wildcards, see [`Device::profile_for_query`](crate::Device::profile_for_query).
+///
+/// ```
+/// use color_manager::qualifier::QualifierQuery;
+///
+/// let query = QualifierQuery::new().exact("RGB")?.any().exact("300dpi")?;
+/// assert_eq!(query.components(), ["RGB", "*", "300dpi"]);
+/// assert_eq!(query.to_string(), "RGB.*.300dpi");
+/// # Ok::<(), color_manager::qualifier::QualifierError>(())
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct QualifierQuery(Vec<Component>);
+
+impl QualifierQuery {
+    /// Creates a query without components.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Appends a component matching only `value`.
+    ///
+    /// Fails if `value` contains a character the daemon does not read
+    /// literally, see [`QualifierError`].
+    pub fn exact(mut self, value: impl Into<String>) -> Result<Self, QualifierError> {
+        let component = Component::Exact(value.into());
+        check_component(&component)?;
+        self.0.push(component);
+
+        Ok(self)
+    }
+
+    /// Appends a component matching any value.
+    #[must_use]
+    pub fn any(mut self) -> Self {
+        self.0.push(Component::Any);
+        self
+    }
+
+    /// The components of the query, with `*` for wildcards.
+    pub fn components(&self) -> Vec<&str> {
+        self.0.iter().map(Component::as_str).collect()
+    }
+
+    /// If the query matches `candidate`, see [`matches()`].
+    pub fn matches(&self, candidate: &str) -> bool {
+        matches(&self.to_string(), candidate)
+    }
+}
+
+impl TryFrom<Vec<Component>> for QualifierQuery {
+    type Error = QualifierError;
+
+    fn try_from(value: Vec<Component>) -> Result<Self, Self::Error> {
+        value.iter().try_for_each(check_component)?;
+
+        Ok(Self(value))
+    }
+}
+
+/// The characters `fnmatch` gives a special meaning, and `.` which separates
+/// the components.
+const SPECIAL_CHARACTERS: [char; 5] = ['*', '?', '[', '\\', '.'];
+
+/// Fails if an exact component would not be sent as a literal value.
+fn check_component(component: &Component) -> Result<(), QualifierError> {
+    match component {
+        Component::Exact(value) if value.contains(SPECIAL_CHARACTERS) => {
+            Err(QualifierError(value.clone()))
+        }
+        _ => Ok(()),
+    }
+}
+
+/// The error returned when an exact component of a [`QualifierQuery`]
+/// contains a `*`, `?`, `[` or `\`, which the daemon does not read
+/// literally, or a `.`, which separates the components.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct QualifierError(String);
+
+impl std::fmt::Display for QualifierError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Invalid exact qualifier component `{}`", self.0)
+    }
+}
+
+impl std::error::Error for QualifierError {}
+
+impl std::fmt::Display for QualifierQuery {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.components().join("."))
+    }
+}
+
+/// Matches a qualifier against a `pattern`, e.g. `RGB.*.300dpi` matches
+/// `RGB.Plain.300dpi` but not `CMYK.Plain.300dpi`.
+///
+/// As in colord, which uses `fnmatch`, the whole pattern is matched against
+/// the whole candidate: `*` matches any sequence of characters, dots included,
+/// so `RGB.*` also matches `RGB.Plain.300dpi`, and `?` matches a single
+/// character. Bracket expressions and backslash escapes are not supported.
+pub fn matches(pattern: &str, candidate: &str) -> bool {
+    glob_match(pattern, candidate)
+}
+
+/// Matches `candidate` against a shell style `pattern`, where `*` matches any
+/// sequence of characters and `?` matches a single character.
+fn glob_match(pattern: &str, candidate: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let candidate: Vec<char> = candidate.chars().collect();
+
+    let (mut p, mut c) = (0, 0);
+    // Position of the last `*` in the pattern and the candidate position it
+    // was matched at, to backtrack to when a later character does not match.
+    let mut star: Option<(usize, usize)> = None;
+    while c < candidate.len() {
+        match pattern.get(p) {
+            Some('*') => {
+                star = Some((p, c));
+                p += 1;
+            }
+            Some('?') => {
+                p += 1;
+                c += 1;
+            }
+            Some(&ch) if ch == candidate[c] => {
+                p += 1;
+                c += 1;
+            }
+            _ => match star {
+                Some((star_p, star_c)) => {
+                    star = Some((star_p, star_c + 1));
+                    p = star_p + 1;
+                    c = star_c + 1;
+                }
+                None => return false,
+            },
+        }
+    }
+
+    pattern[p..].iter().all(|&ch| ch == '*')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn glob() {
+        assert!(glob_match("RGB.Plain.300dpi", "RGB.Plain.300dpi"));
+        assert!(glob_match("RGB.*.300dpi", "RGB.Plain.300dpi"));
+        assert!(glob_match("RGB.*.*", "RGB.Plain.300dpi"));
+        assert!(glob_match("*", "RGB.Plain.300dpi"));
+        assert!(glob_match("RGB.Plain.???dpi", "RGB.Plain.300dpi"));
+        assert!(glob_match("*.300dpi", "RGB.Glossy.300dpi"));
+
+        assert!(!glob_match("RGB.*.300dpi", "CMYK.Plain.300dpi"));
+        assert!(!glob_match("RGB.Plain.??dpi", "RGB.Plain.300dpi"));
+        assert!(!glob_match("RGB.*.600dpi", "RGB.Plain.300dpi"));
+        assert!(!glob_match("", "RGB.Plain.300dpi"));
+    }
+
+    #[test]
+    fn whole_string() {
+        assert!(matches("RGB.*.300dpi", "RGB.Plain.300dpi"));
+        assert!(matches("RGB.*.*", "RGB.Glossy.600dpi"));
+        assert!(matches("*.*.*", "CMYK.Plain.300dpi"));
+        assert!(matches("RGB.Pl?in.300dpi", "RGB.Plain.300dpi"));
+        assert!(matches("RGB.*.*dpi", "RGB.Plain.1200dpi"));
+        assert!(matches("RGB.Plain.300dpi", "RGB.Plain.300dpi"));
+        // Wildcards match across components, as with `fnmatch`.
+        assert!(matches("RGB.*", "RGB.Plain.300dpi"));
+        assert!(matches("*", "RGB.Plain.300dpi"));
+        assert!(matches("RGB*dpi", "RGB.Plain.300dpi"));
+
+        assert!(!matches("RGB.*.300dpi", "CMYK.Plain.300dpi"));
+        assert!(!matches("RGB.*.300dpi", "RGB.Plain.600dpi"));
+        assert!(!matches("RGB.*.*.*", "RGB.Plain.300dpi"));
+        assert!(!matches("RGB.?.300dpi", "RGB.Plain.300dpi"));
+    }
+
+    #[test]
+    fn query() {
+        let query = QualifierQuery::new()
+            .exact("RGB")
+            .unwrap()
+            .any()
+            .exact("300dpi")
+            .unwrap();
+        assert_eq!(query.components(), ["RGB", "*", "300dpi"]);
+        assert_eq!(query.to_string(), "RGB.*.300dpi");
+        assert!(query.matches("RGB.Plain.300dpi"));
+        assert!(!query.matches("RGB.Plain.600dpi"));
+        assert!(!query.matches("RGB.Plain"));
+        assert!(!query.matches("RGB.Plain.300dpi.Extra"));
+        assert!(query.matches("RGB.Plain.Glossy.300dpi"));
+
+        assert_eq!(QualifierQuery::new().to_string(), "");
+    }
+
+    #[test]
+    fn query_exact_special() {
+        for value in ["Pl*in", "Pl?in", "Pl[a]in", "Pl\\ain", "RGB.Plain"] {
+            assert_eq!(
+                QualifierQuery::new().exact(value),
+                Err(QualifierError(value.to_owned()))
+            );
+            let components = vec![Component::Any, Component::Exact(value.to_owned())];
+            assert!(QualifierQuery::try_from(components).is_err());
+        }
+        let components = vec![Component::Exact("RGB".to_owned()), Component::Any];
+        assert_eq!(
+            QualifierQuery::try_from(components).unwrap().to_string(),
+            "RGB.*"
+        );
+    }
+
+    #[test]
+    fn format() {
+        let format = Format::from("ColorModel.OutputMode.OutputResolution".to_owned());
+        assert_eq!(
+            format.components(),
+            ["ColorModel", "OutputMode", "OutputResolution"]
+        );
+        assert_eq!(format.color_model(), Some("ColorModel"));
+        assert_eq!(format.output_mode(), Some("OutputMode"));
+        assert_eq!(format.output_resolution(), Some("OutputResolution"));
+
+        let format = Format::from("ColorModel".to_owned());
+        assert_eq!(format.components(), ["ColorModel"]);
+        assert_eq!(format.output_mode(), None);
+
+        let format = Format::from("A.B.C.D".to_owned());
+        assert_eq!(format.components().len(), 4);
+        assert_eq!(format.output_resolution(), Some("C"));
+
+        assert!(Format::from(String::new()).components().is_empty());
+    }
+}
@@ -1,27 +1,70 @@
+use std::{fmt, str::FromStr};
+
 use serde::{Deserialize, Serialize};
-use zbus::zvariant::{self, Type};
+use zbus::zvariant::Type;
+
+use crate::wire::{self, impl_from_owned_value};
 
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Type)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Type, Default)]
 #[zvariant(signature = "s")]
 #[serde(rename_all = "lowercase")]
 pub enum Scope {
+    #[default]
     Normal,
     Temp,
     Disk,
 }
 
-impl Default for Scope {
-    fn default() -> Self {
-        Self::Normal
+impl Scope {
+    /// The string used for the scope on the wire, e.g. `temp`, as sent when
+    /// creating a device or profile.
+    pub fn as_wire_str(&self) -> &'static str {
+        match self {
+            Self::Normal => "normal",
+            Self::Temp => "temp",
+            Self::Disk => "disk",
+        }
     }
 }
 
-impl From<zvariant::OwnedValue> for Scope {
-    fn from(value: zvariant::OwnedValue) -> Self {
-        match value.downcast_ref::<zvariant::Str>().unwrap().as_str() {
-            "temp" => Self::Temp,
-            "disk" => Self::Disk,
-            _ => Self::default(),
+impl_from_owned_value!(Scope, Self::default());
+
+impl fmt::Display for Scope {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_wire_str())
+    }
+}
+
+impl FromStr for Scope {
+    type Err = ParseScopeError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        wire::from_wire_str(s).ok_or_else(|| ParseScopeError(s.to_owned()))
+    }
+}
+
+/// The error returned when parsing an unknown [`Scope`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseScopeError(String);
+
+impl fmt::Display for ParseScopeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Unknown scope `{}`", self.0)
+    }
+}
+
+impl std::error::Error for ParseScopeError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn scope_round_trip() {
+        for scope in [Scope::Normal, Scope::Temp, Scope::Disk] {
+            assert_eq!(scope.to_string().parse::<Scope>(), Ok(scope));
         }
+        assert_eq!(Scope::Temp.to_string(), "temp");
+        assert!("Temp".parse::<Scope>().is_err());
     }
 }
@@ -1,12 +1,38 @@
-use std::collections::HashMap;
+use std::{
+    collections::HashMap,
+    hash::{Hash, Hasher},
+    path::PathBuf,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+    time::Duration,
+};
 
+use enumflags2::{bitflags, BitFlags};
 use futures_util::StreamExt;
 use serde::{Deserialize, Serialize};
 use zbus::{
-    zvariant::{ObjectPath, Type, Value},
+    zvariant::{ObjectPath, OwnedObjectPath, OwnedValue, Type, Value},
     Result,
 };
 
+use crate::{
+    config::{self, PropertyCache},
+    error,
+    trace::ProxyExt,
+    wire::{self, impl_from_owned_value},
+    ClientConfig,
+};
+
+/// Metadata key for an image showing how to attach the sensor to the screen.
+pub const METADATA_ATTACH_IMAGE: &str = "AttachImage";
+/// Metadata key for an image showing how to calibrate the sensor.
+pub const METADATA_CALIBRATE_IMAGE: &str = "CalibrateImage";
+/// Metadata key for an image showing how to measure the screen with the
+/// sensor.
+pub const METADATA_SCREEN_IMAGE: &str = "ScreenImage";
+
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Type)]
 #[zvariant(signature = "s")]
 #[serde(rename_all = "lowercase")]
@@ -16,36 +42,432 @@ pub enum Mode {
     Unknown,
 }
 
-impl From<zbus::zvariant::OwnedValue> for Mode {
-    fn from(value: zbus::zvariant::OwnedValue) -> Self {
-        match value
-            .downcast_ref::<zbus::zvariant::Str>()
-            .unwrap()
-            .as_str()
-        {
-            "ambient" => Self::Ambient,
-            "printer" => Self::Printer,
-            _ => Self::Unknown,
-        }
-    }
-}
+impl_from_owned_value!(Mode, Self::Unknown);
 
-#[derive(Debug, Clone, Serialize, PartialEq, Eq, Type)]
+/// The state of a sensor, see [`Sensor::state_typed`].
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Type)]
 #[zvariant(signature = "s")]
 #[serde(rename_all = "lowercase")]
+pub enum State {
+    Starting,
+    Idle,
+    Measuring,
+    Busy,
+    Unknown,
+}
+
+impl_from_owned_value!(State, Self::Unknown);
+
+/// A sensor together with the properties shown when picking an instrument,
+/// see [`ColorManager::sensors_with_state`](crate::ColorManager::sensors_with_state).
+#[derive(Debug, Clone)]
+pub struct SensorSummary<'a> {
+    pub sensor: Sensor<'a>,
+    pub model: String,
+    pub vendor: String,
+    pub state: State,
+}
+
+/// A capability of a sensor, i.e. what it can measure.
+///
+/// colord uses a single list for both the kind of measurement, e.g.
+/// [`Capability::Printer`] or [`Capability::Ambient`], and the display
+/// technologies the sensor is calibrated for, e.g. [`Capability::Crt`] or
+/// [`Capability::LcdCcfl`]. The variants follow `CdSensorCap` of libcolord,
+/// the string on the wire is the lowercase name with `-` between words, e.g.
+/// `lcd-rgb-led` for [`Capability::LcdRgbLed`].
+///
+/// The discriminants are the bits used for each capability in the
+/// `CdSensorCap` bitfield of libcolord, see [`Sensor::capabilities_bitfield`].
+#[bitflags]
+#[repr(u64)]
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Type)]
+#[zvariant(signature = "s")]
+#[serde(rename_all = "kebab-case")]
 pub enum Capability {
-    Crt,
-    Ambient,
-    Lcd,
-    Led,
-    Projector,
+    Lcd = 1 << 1,
+    Crt = 1 << 2,
+    Printer = 1 << 3,
+    /// Measures spot colors, e.g. of a paint sample.
+    Spot = 1 << 4,
+    Projector = 1 << 5,
+    Ambient = 1 << 6,
+    /// Can be calibrated, e.g. by placing it on its calibration tile.
+    Calibration = 1 << 7,
+    Led = 1 << 8,
+    Plasma = 1 << 9,
+    LcdCcfl = 1 << 10,
+    LcdRgbLed = 1 << 11,
+    LcdWhiteLed = 1 << 12,
+    WideGamutLcdCcfl = 1 << 13,
+    WideGamutLcdRgbLed = 1 << 14,
+    Unknown = 1 << 0,
 }
 
-#[derive(Type, Debug)]
+impl Capability {
+    fn from_wire(value: &str) -> Self {
+        wire::from_wire_str(value).unwrap_or(Self::Unknown)
+    }
+
+    /// Converts a `CdSensorCap` bitfield into the capabilities it contains,
+    /// bits of capabilities not known to this crate are ignored.
+    pub fn from_bitfield(bitfield: u64) -> Vec<Capability> {
+        BitFlags::<Capability>::from_bits_truncate(bitfield)
+            .iter()
+            .collect()
+    }
+}
+
+impl_from_owned_value!(Capability, Self::Unknown);
+
+/// The mean of several XYZ samples, see [`Sensor::sample_average`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct XyzReading {
+    /// The mean of each component.
+    pub xyz: (f64, f64, f64),
+    /// The population standard deviation of each component.
+    pub std_dev: (f64, f64, f64),
+    /// The number of samples the reading is made of.
+    pub samples: usize,
+}
+
+impl XyzReading {
+    /// The CIE D65 standard illuminant, normalized to `Y = 1`.
+    pub const D65: XyzReading = XyzReading::from_xyz(0.95047, 1.0, 1.08883);
+    /// The CIE D50 standard illuminant, normalized to `Y = 1`.
+    pub const D50: XyzReading = XyzReading::from_xyz(0.96422, 1.0, 0.82521);
+
+    /// A reading made of a single exact sample, e.g. a reference white.
+    pub const fn from_xyz(x: f64, y: f64, z: f64) -> Self {
+        Self {
+            xyz: (x, y, z),
+            std_dev: (0.0, 0.0, 0.0),
+            samples: 1,
+        }
+    }
+
+    /// Converts the mean of the reading to CIE L\*a\*b\* relative to the
+    /// reference `white`, e.g. [`XyzReading::D65`], which has to be on the same
+    /// scale as the reading.
+    pub fn to_lab(&self, white: XyzReading) -> LabReading {
+        // The cube root, with a linear segment near zero.
+        fn f(t: f64) -> f64 {
+            const DELTA: f64 = 6.0 / 29.0;
+            if t > DELTA.powi(3) {
+                t.cbrt()
+            } else {
+                t / (3.0 * DELTA.powi(2)) + 4.0 / 29.0
+            }
+        }
+
+        let fx = f(self.xyz.0 / white.xyz.0);
+        let fy = f(self.xyz.1 / white.xyz.1);
+        let fz = f(self.xyz.2 / white.xyz.2);
+
+        LabReading {
+            l: 116.0 * fy - 16.0,
+            a: 500.0 * (fx - fy),
+            b: 200.0 * (fy - fz),
+        }
+    }
+
+    fn from_samples(samples: &[(f64, f64, f64)]) -> Self {
+        let n = samples.len() as f64;
+        let mean = |component: &dyn Fn(&(f64, f64, f64)) -> f64| {
+            samples.iter().map(component).sum::<f64>() / n
+        };
+        let xyz = (mean(&|s| s.0), mean(&|s| s.1), mean(&|s| s.2));
+        let std_dev = (
+            mean(&|s| (s.0 - xyz.0).powi(2)).sqrt(),
+            mean(&|s| (s.1 - xyz.1).powi(2)).sqrt(),
+            mean(&|s| (s.2 - xyz.2).powi(2)).sqrt(),
+        );
+
+        Self {
+            xyz,
+            std_dev,
+            samples: samples.len(),
+        }
+    }
+}
+
+/// A color in the CIE L\*a\*b\* space, see [`XyzReading::to_lab`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LabReading {
+    /// The lightness, from 0 for black to 100 for the reference white.
+    pub l: f64,
+    /// The green (negative) to red (positive) component.
+    pub a: f64,
+    /// The blue (negative) to yellow (positive) component.
+    pub b: f64,
+}
+
+impl LabReading {
+    /// The CIE76 color difference, the euclidean distance between the colors.
+    ///
+    /// A difference of about 2.3 is just noticeable.
+    pub fn delta_e_76(&self, other: &LabReading) -> f64 {
+        ((self.l - other.l).powi(2) + (self.a - other.a).powi(2) + (self.b - other.b).powi(2))
+            .sqrt()
+    }
+
+    /// The CIEDE2000 color difference, which corrects the perceptual
+    /// non-uniformities of [`LabReading::delta_e_76`], with the weighting
+    /// factors `kL = kC = kH = 1`.
+    pub fn delta_e_2000(&self, other: &LabReading) -> f64 {
+        const POW25_7: f64 = 6_103_515_625.0;
+
+        // The hue angle in degrees in `[0, 360)`, zero for achromatic colors.
+        fn hue(a: f64, b: f64) -> f64 {
+            if a == 0.0 && b == 0.0 {
+                return 0.0;
+            }
+            b.atan2(a).to_degrees().rem_euclid(360.0)
+        }
+
+        let (l1, a1, b1) = (self.l, self.a, self.b);
+        let (l2, a2, b2) = (other.l, other.a, other.b);
+
+        let c_bar = (a1.hypot(b1) + a2.hypot(b2)) / 2.0;
+        let g = 0.5 * (1.0 - (c_bar.powi(7) / (c_bar.powi(7) + POW25_7)).sqrt());
+        let (a1, a2) = ((1.0 + g) * a1, (1.0 + g) * a2);
+        let (c1, c2) = (a1.hypot(b1), a2.hypot(b2));
+        let (h1, h2) = (hue(a1, b1), hue(a2, b2));
+
+        let delta_l = l2 - l1;
+        let delta_c = c2 - c1;
+        let delta_h = if c1 * c2 == 0.0 {
+            0.0
+        } else if (h2 - h1).abs() <= 180.0 {
+            h2 - h1
+        } else if h2 - h1 > 180.0 {
+            h2 - h1 - 360.0
+        } else {
+            h2 - h1 + 360.0
+        };
+        let delta_h = 2.0 * (c1 * c2).sqrt() * (delta_h / 2.0).to_radians().sin();
+
+        let l_bar = (l1 + l2) / 2.0;
+        let c_bar = (c1 + c2) / 2.0;
+        let h_bar = if c1 * c2 == 0.0 {
+            h1 + h2
+        } else if (h1 - h2).abs() <= 180.0 {
+            (h1 + h2) / 2.0
+        } else if h1 + h2 < 360.0 {
+            (h1 + h2 + 360.0) / 2.0
+        } else {
+            (h1 + h2 - 360.0) / 2.0
+        };
+
+        let cos = |degrees: f64| degrees.to_radians().cos();
+        let t = 1.0 - 0.17 * cos(h_bar - 30.0)
+            + 0.24 * cos(2.0 * h_bar)
+            + 0.32 * cos(3.0 * h_bar + 6.0)
+            - 0.20 * cos(4.0 * h_bar - 63.0);
+        let delta_theta = 30.0 * (-((h_bar - 275.0) / 25.0).powi(2)).exp();
+        let r_c = 2.0 * (c_bar.powi(7) / (c_bar.powi(7) + POW25_7)).sqrt();
+        let s_l = 1.0 + 0.015 * (l_bar - 50.0).powi(2) / (20.0 + (l_bar - 50.0).powi(2)).sqrt();
+        let s_c = 1.0 + 0.045 * c_bar;
+        let s_h = 1.0 + 0.015 * c_bar * t;
+        let r_t = -(2.0 * delta_theta).to_radians().sin() * r_c;
+
+        let (l, c, h) = (delta_l / s_l, delta_c / s_c, delta_h / s_h);
+        (l.powi(2) + c.powi(2) + h.powi(2) + r_t * c * h).sqrt()
+    }
+}
+
+/// A spectrum measured by a sensor, see [`Sensor::spectrum_typed`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct Spectrum {
+    /// The wavelength of the first value, in nm.
+    pub start: f64,
+    /// The wavelength of the last value, in nm.
+    pub end: f64,
+    /// The values, evenly spaced between `start` and `end`.
+    pub values: Vec<f64>,
+}
+
+impl Spectrum {
+    /// The spectrum scaled so that its largest value is `1.0`.
+    ///
+    /// A spectrum without positive values is returned unchanged.
+    pub fn normalized(&self) -> Spectrum {
+        let max = self.values.iter().copied().fold(f64::NAN, f64::max);
+        let values = if max > 0.0 {
+            self.values.iter().map(|value| value / max).collect()
+        } else {
+            self.values.clone()
+        };
+
+        Self {
+            values,
+            ..self.clone()
+        }
+    }
+
+    fn average(spectra: &[Spectrum]) -> Result<Self> {
+        let first = &spectra[0];
+        if spectra.iter().any(|spectrum| {
+            spectrum.values.len() != first.values.len()
+                || spectrum.start != first.start
+                || spectrum.end != first.end
+        }) {
+            return Err(zbus::Error::Failure(
+                "The scans have different sample counts or ranges".into(),
+            ));
+        }
+        let n = spectra.len() as f64;
+        let values = (0..first.values.len())
+            .map(|i| {
+                spectra
+                    .iter()
+                    .map(|spectrum| spectrum.values[i])
+                    .sum::<f64>()
+                    / n
+            })
+            .collect();
+
+        Ok(Self {
+            values,
+            ..first.clone()
+        })
+    }
+}
+
+impl From<(f64, f64, Vec<f64>)> for Spectrum {
+    fn from((start, end, values): (f64, f64, Vec<f64>)) -> Self {
+        Self { start, end, values }
+    }
+}
+
+/// The keys of the options that can be set with [`Sensor::set_option`].
+///
+/// Of the drivers shipped with colord only the dummy sensor accepts options,
+/// the `sample[red]`, `sample[green]` and `sample[blue]` components of the
+/// color it returns, so the known keys cover the dummy sensor only. The other
+/// drivers reject any option with a `NoSupport` error. The daemon provides
+/// no way to read the options back.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum SensorOption {
+    /// The red component returned by the dummy sensor, a `f64`.
+    SampleRed,
+    /// The green component returned by the dummy sensor, a `f64`.
+    SampleGreen,
+    /// The blue component returned by the dummy sensor, a `f64`.
+    SampleBlue,
+    /// An option not known to this crate, its value is not checked.
+    Other(String),
+}
+
+impl SensorOption {
+    /// The key used on the wire, e.g. `sample[red]`.
+    pub fn as_str(&self) -> &str {
+        match self {
+            Self::SampleRed => "sample[red]",
+            Self::SampleGreen => "sample[green]",
+            Self::SampleBlue => "sample[blue]",
+            Self::Other(key) => key,
+        }
+    }
+
+    fn check(&self, value: &Value<'_>) -> Result<()> {
+        let expected = match self {
+            Self::SampleRed | Self::SampleGreen | Self::SampleBlue => "d",
+            Self::Other(_) => return Ok(()),
+        };
+        if value.value_signature() != expected {
+            return Err(zbus::Error::Variant(zbus::zvariant::Error::IncorrectType));
+        }
+
+        Ok(())
+    }
+}
+
+/// A set of options of possibly different types, see
+/// [`Sensor::set_options_typed`].
+///
+/// ```
+/// use color_manager::sensor::{SensorOption, SensorOptions};
+///
+/// let options = SensorOptions::new()
+///     .set_f64(SensorOption::SampleRed, 1.0)
+///     .set_f64(SensorOption::SampleGreen, 0.5)
+///     .set_f64(SensorOption::SampleBlue, 0.0);
+/// assert_eq!(options.len(), 3);
+/// ```
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SensorOptions<'o>(HashMap<SensorOption, Value<'o>>);
+
+impl<'o> SensorOptions<'o> {
+    /// Creates an empty set of options.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the string option `key`.
+    #[must_use]
+    pub fn set_str(mut self, key: SensorOption, value: &'o str) -> Self {
+        self.0.insert(key, value.into());
+        self
+    }
+
+    /// Sets the unsigned integer option `key`.
+    #[must_use]
+    pub fn set_u32(mut self, key: SensorOption, value: u32) -> Self {
+        self.0.insert(key, value.into());
+        self
+    }
+
+    /// Sets the boolean option `key`.
+    #[must_use]
+    pub fn set_bool(mut self, key: SensorOption, value: bool) -> Self {
+        self.0.insert(key, value.into());
+        self
+    }
+
+    /// Sets the floating point option `key`, e.g.
+    /// [`SensorOption::SampleRed`].
+    #[must_use]
+    pub fn set_f64(mut self, key: SensorOption, value: f64) -> Self {
+        self.0.insert(key, value.into());
+        self
+    }
+
+    /// The number of options.
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    /// If no option is set.
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+}
+
+impl<'o, V: Into<Value<'o>>> From<HashMap<SensorOption, V>> for SensorOptions<'o> {
+    fn from(value: HashMap<SensorOption, V>) -> Self {
+        Self(value.into_iter().map(|(k, v)| (k, v.into())).collect())
+    }
+}
+
+impl<'o> From<SensorOptions<'o>> for HashMap<SensorOption, Value<'o>> {
+    fn from(value: SensorOptions<'o>) -> Self {
+        value.0
+    }
+}
+
+#[derive(Type, Clone)]
 #[zvariant(signature = "o")]
 #[doc(alias = "org.freedesktop.ColorManager.Sensor")]
 /// A wrapper of the `org.freedesktop.ColorManager.Sensor` DBus object.
-pub struct Sensor<'a>(zbus::Proxy<'a>);
+///
+/// Clones share whether the lock is held, see [`Sensor::held_by_self`].
+pub struct Sensor<'a>(
+    zbus::Proxy<'a>,
+    Arc<AtomicBool>,
+    Arc<ClientConfig>,
+    PropertyCache,
+);
 
 impl<'a> Sensor<'a> {
     pub async fn new<P>(connection: &zbus::Connection, object_path: P) -> Result<Sensor<'a>>
@@ -53,19 +475,37 @@ impl<'a> Sensor<'a> {
         P: TryInto<ObjectPath<'a>>,
         P::Error: Into<zbus::Error>,
     {
-        let inner = zbus::ProxyBuilder::new_bare(connection)
-            .interface("org.freedesktop.ColorManager.Sensor")?
-            .path(object_path)?
-            .destination("org.freedesktop.ColorManager")?
-            .cache_properties(zbus::CacheProperties::No)
-            .build()
+        Self::with_config(connection, object_path, Arc::default()).await
+    }
+
+    pub(crate) async fn with_config<P>(
+        connection: &zbus::Connection,
+        object_path: P,
+        config: Arc<ClientConfig>,
+    ) -> Result<Sensor<'a>>
+    where
+        P: TryInto<ObjectPath<'a>>,
+        P::Error: Into<zbus::Error>,
+    {
+        let inner = config
+            .proxy(
+                connection,
+                "org.freedesktop.ColorManager.Sensor",
+                object_path,
+            )
             .await?;
-        Ok(Self(inner))
+        Ok(Self(
+            inner,
+            Arc::default(),
+            config,
+            PropertyCache::default(),
+        ))
     }
 
     pub(crate) async fn from_paths<P>(
         connection: &zbus::Connection,
         paths: Vec<P>,
+        config: Arc<ClientConfig>,
     ) -> Result<Vec<Sensor<'a>>>
     where
         P: TryInto<ObjectPath<'a>>,
@@ -73,22 +513,43 @@ impl<'a> Sensor<'a> {
     {
         let mut items = Vec::with_capacity(paths.capacity());
         for path in paths.into_iter() {
-            items.push(Self::new(connection, path).await?);
+            items.push(Self::with_config(connection, path, config.clone()).await?);
         }
         Ok(items)
     }
 
-    pub fn inner(&self) -> &zbus::Proxy {
+    pub fn inner(&self) -> &zbus::Proxy<'_> {
         &self.0
     }
 
+    /// The settings this object was created with.
+    pub fn config(&self) -> &ClientConfig {
+        &self.2
+    }
+
+    pub(crate) fn shared_config(&self) -> Arc<ClientConfig> {
+        self.2.clone()
+    }
+
+    /// Drops the property values cached with
+    /// [`ClientConfig::cache_properties`], the next reads get them from the
+    /// daemon again. Does nothing if the properties are not cached.
+    ///
+    /// Clones of the sensor are refreshed too.
+    pub async fn refresh(&self) -> Result<()> {
+        self.3
+            .refresh(&self.2, &self.0, "org.freedesktop.ColorManager.Sensor")
+            .await
+    }
+
     #[doc(alias = "Lock")]
     /// Locks the sensor for use by an application.
     ///
     /// If the current holder of the lock quits without calling Unlock then it
     /// is automatically removed.
     pub async fn lock(&self) -> Result<()> {
-        self.inner().call_method("Lock", &()).await?;
+        self.traced_call("Lock", &()).await?;
+        self.1.store(true, Ordering::Release);
 
         Ok(())
     }
@@ -96,30 +557,114 @@ impl<'a> Sensor<'a> {
     #[doc(alias = "Unlock")]
     /// Unlocks the sensor for use by other applications.
     pub async fn unlock(&self) -> Result<()> {
-        self.inner().call_method("Unlock", &()).await?;
+        self.traced_call("Unlock", &()).await?;
+        self.1.store(false, Ordering::Release);
 
         Ok(())
     }
 
+    /// If the lock was taken with [`Sensor::lock`] through this wrapper, or a
+    /// clone of it, and not released since.
+    ///
+    /// Unlike [`Sensor::locked`] this does not ask the daemon, which also
+    /// reports locks held by other applications.
+    pub fn held_by_self(&self) -> bool {
+        self.1.load(Ordering::Acquire)
+    }
+
+    /// Forgets the lock without unlocking the sensor, used when the unlock
+    /// happens in the background.
+    pub(crate) fn forget_lock(&self) {
+        self.1.store(false, Ordering::Release);
+    }
+
     #[doc(alias = "GetSample")]
     /// Gets a color sample using the sensor.
     pub async fn sample(&self, capability: Capability) -> Result<(f64, f64, f64)> {
-        let msg = self.inner().call_method("GetSample", &(capability)).await?;
+        let msg = self.traced_call("GetSample", &(capability)).await?;
 
         msg.body()
     }
 
+    #[doc(alias = "GetSample")]
+    /// Takes `n` samples one after the other and returns their mean, to reduce
+    /// the noise of the sensor. Fails if `n` is zero.
+    pub async fn sample_average(&self, capability: Capability, n: usize) -> Result<XyzReading> {
+        if n == 0 {
+            return Err(zbus::Error::Failure(
+                "At least one sample has to be taken".into(),
+            ));
+        }
+        let mut samples = Vec::with_capacity(n);
+        for _ in 0..n {
+            samples.push(self.sample(capability).await?);
+        }
+
+        Ok(XyzReading::from_samples(&samples))
+    }
+
+    #[doc(alias = "Capabilities")]
+    /// If the sensor has a calibration step that has to be done before
+    /// measuring, e.g. reading the calibration tile of a ColorMunki or the
+    /// dark calibration of an i1Pro. Such sensors usually also provide a
+    /// [`METADATA_CALIBRATE_IMAGE`] showing how to position them.
+    ///
+    /// The daemon does not report whether a calibration is currently due, so
+    /// this is `true` for every sensor with [`Capability::Calibration`].
+    pub async fn needs_calibration(&self) -> Result<bool> {
+        self.supports(Capability::Calibration).await
+    }
+
+    #[doc(alias = "GetSample")]
+    /// Calibrates the sensor by taking a [`Capability::Calibration`] sample,
+    /// the sensor has to be in its calibration position, see
+    /// [`Sensor::needs_calibration`].
+    ///
+    /// Fails without contacting the daemon if the sensor cannot be
+    /// calibrated.
+    pub async fn calibrate(&self) -> Result<()> {
+        if !self.needs_calibration().await? {
+            return Err(zbus::Error::Failure(
+                "The sensor cannot be calibrated".into(),
+            ));
+        }
+        self.sample(Capability::Calibration).await?;
+
+        Ok(())
+    }
+
     #[doc(alias = "GetSpectrum")]
     /// Gets a color spectrum using the sensor.
     pub async fn spectrum(&self, capability: Capability) -> Result<(f64, f64, Vec<f64>)> {
-        let msg = self
-            .inner()
-            .call_method("GetSpectrum", &(capability))
-            .await?;
+        let msg = self.traced_call("GetSpectrum", &(capability)).await?;
 
         msg.body()
     }
 
+    #[doc(alias = "GetSpectrum")]
+    /// Like [`Sensor::spectrum`] but returns a [`Spectrum`].
+    pub async fn spectrum_typed(&self, capability: Capability) -> Result<Spectrum> {
+        self.spectrum(capability).await.map(Spectrum::from)
+    }
+
+    #[doc(alias = "GetSpectrum")]
+    /// Takes `n` spectra with the sensor and averages them value-wise.
+    ///
+    /// Fails if `n` is zero or if the scans do not have the same sample count.
+    pub async fn spectrum_average(&self, capability: Capability, n: usize) -> Result<Spectrum> {
+        if n == 0 {
+            return Err(zbus::Error::Failure(
+                "At least one spectrum has to be taken".into(),
+            ));
+        }
+        let mut spectra = Vec::with_capacity(n);
+        for _ in 0..n {
+            spectra.push(self.spectrum_typed(capability).await?);
+        }
+
+        Spectrum::average(&spectra)
+    }
+
     #[doc(alias = "SetOptions")]
     /// Sets one or multiple options on the sensor.
     pub async fn set_options<V: Into<Value<'a>>>(&self, values: HashMap<&str, V>) -> Result<()> {
@@ -127,11 +672,40 @@ impl<'a> Sensor<'a> {
             .into_iter()
             .map(|(k, v)| (k, v.into()))
             .collect::<HashMap<&str, Value<'a>>>();
-        self.inner().call_method("SetOptions", &(map)).await?;
+        self.traced_call("SetOptions", &(map)).await?;
+
+        Ok(())
+    }
+
+    #[doc(alias = "SetOptions")]
+    /// Sets one or multiple options on the sensor.
+    ///
+    /// `options` is either a [`SensorOptions`], which can hold values of
+    /// different types, or a `HashMap<SensorOption, V>`.
+    ///
+    /// Returns an error without contacting the daemon if the value of a known
+    /// option has the wrong type.
+    pub async fn set_options_typed<'o>(&self, options: impl Into<SensorOptions<'o>>) -> Result<()> {
+        let options = options.into();
+        for (key, value) in &options.0 {
+            key.check(value)?;
+        }
+        let map = options
+            .0
+            .iter()
+            .map(|(k, v)| (k.as_str(), v))
+            .collect::<HashMap<&str, &Value<'o>>>();
+        self.traced_call("SetOptions", &(map)).await?;
 
         Ok(())
     }
 
+    #[doc(alias = "SetOptions")]
+    /// Sets a single option on the sensor, see [`Sensor::set_options_typed`].
+    pub async fn set_option<V: Into<Value<'a>>>(&self, key: SensorOption, value: V) -> Result<()> {
+        self.set_options_typed(HashMap::from([(key, value)])).await
+    }
+
     #[doc(alias = "ButtonPressed")]
     /// A button on the sensor has been pressed.
     pub async fn button_pressed(&self) -> Result<()> {
@@ -147,20 +721,51 @@ impl<'a> Sensor<'a> {
     #[doc(alias = "SensorId")]
     /// The sensor id string.
     pub async fn sensor_id(&self) -> Result<String> {
-        self.inner().get_property("SensorId").await
+        self.traced_property("SensorId").await
     }
 
     // TODO Use enum?.
     #[doc(alias = "Kind")]
     /// The kind of the sensor, e.g. `colormunki`
     pub async fn kind(&self) -> Result<String> {
-        self.inner().get_property("Kind").await
+        self.traced_property("Kind").await
     }
 
     #[doc(alias = "State")]
     /// The state of the sensor, e.g. `starting`, `idle` or `measuring`.
     pub async fn state(&self) -> Result<String> {
-        self.inner().get_property("State").await
+        self.traced_property("State").await
+    }
+
+    #[doc(alias = "State")]
+    /// Like [`Sensor::state`] but returns a [`State`].
+    pub async fn state_typed(&self) -> Result<State> {
+        self.traced_property::<State>("State").await
+    }
+
+    #[doc(alias = "State")]
+    /// Waits until the sensor is in the `target` state, e.g. back to
+    /// [`State::Idle`] after a measurement.
+    ///
+    /// The state is read again each time the properties of the sensor change.
+    /// Fails with a [`std::io::ErrorKind::TimedOut`] error if the state was not
+    /// reached after `timeout`.
+    pub async fn wait_for_state(&self, target: State, timeout: Duration) -> Result<()> {
+        let properties = config::properties_proxy(self.inner()).await?;
+        let mut stream = properties.receive_signal("PropertiesChanged").await?;
+
+        crate::timeout::timeout(timeout, async {
+            loop {
+                if self.state_typed().await? == target {
+                    return Ok(());
+                }
+                stream
+                    .next()
+                    .await
+                    .ok_or(zbus::Error::Failure("No response".into()))?;
+            }
+        })
+        .await
     }
 
     #[doc(alias = "Mode")]
@@ -170,52 +775,133 @@ impl<'a> Sensor<'a> {
     /// reading can be taken. This property should be set to the current device
     /// mode.
     pub async fn mode(&self) -> Result<Mode> {
-        self.inner().get_property::<Mode>("Mode").await
+        self.traced_property::<Mode>("Mode").await
     }
 
     #[doc(alias = "Serial")]
-    /// The sensor serial number, e.g. `012345678a`.
-    pub async fn serial(&self) -> Result<String> {
-        self.inner().get_property("Serial").await
+    /// The sensor serial number, e.g. `012345678a`, `None` if the sensor does
+    /// not report one.
+    pub async fn serial(&self) -> Result<Option<String>> {
+        error::optional_property(self.traced_property("Serial").await)
     }
 
     #[doc(alias = "Model")]
     /// The sensor model, e.g. `ColorMunki`.
     pub async fn model(&self) -> Result<String> {
-        self.inner().get_property("Model").await
+        self.traced_property("Model").await
     }
 
     #[doc(alias = "Vendor")]
     /// The sensor vendor, e.g. `XRite`.
     pub async fn vendor(&self) -> Result<String> {
-        self.inner().get_property("Vendor").await
+        self.traced_property("Vendor").await
     }
 
     #[doc(alias = "Native")]
     /// If the sensor is supported with a native driver, which does not require
     /// additional tools such as argyllcms.
     pub async fn native(&self) -> Result<bool> {
-        self.inner().get_property("Native").await
+        self.traced_property("Native").await
     }
 
     #[doc(alias = "Locked")]
     /// If the sensor is locked for use by colord.
     pub async fn locked(&self) -> Result<bool> {
-        self.inner().get_property("Locked").await
+        self.traced_property("Locked").await
     }
 
     #[doc(alias = "Capabilities")]
-    /// The capabilities of the sensor, e.g `['display', 'printer', 'projector',
-    /// 'spot']`.
+    /// The capabilities of the sensor, e.g `['lcd', 'printer', 'projector',
+    /// 'spot']`, see [`Capability`] for the known values.
     pub async fn capabilities(&self) -> Result<Vec<String>> {
-        self.inner().get_property("Capabilities").await
+        self.traced_property("Capabilities").await
+    }
+
+    #[doc(alias = "Capabilities")]
+    /// The capabilities of the sensor.
+    ///
+    /// Capabilities that are not known to this crate are skipped.
+    pub async fn capabilities_typed(&self) -> Result<Vec<Capability>> {
+        let capabilities = self
+            .capabilities()
+            .await?
+            .iter()
+            .map(|capability| Capability::from_wire(capability))
+            .filter(|capability| *capability != Capability::Unknown)
+            .collect();
+
+        Ok(capabilities)
+    }
+
+    #[doc(alias = "Capabilities")]
+    /// The capabilities of the sensor as a `CdSensorCap` bitfield, as returned
+    /// by `cd_sensor_get_caps` in libcolord.
+    ///
+    /// colord only exposes the capabilities as a list of strings over DBus, so
+    /// the bitfield is computed from [`Sensor::capabilities_typed`], see
+    /// [`Capability::from_bitfield`] for the reverse conversion.
+    pub async fn capabilities_bitfield(&self) -> Result<u64> {
+        let capabilities = self.capabilities_typed().await?;
+
+        Ok(BitFlags::<Capability>::from_iter(capabilities).bits())
+    }
+
+    #[doc(alias = "Capabilities")]
+    /// If the sensor has the given capability.
+    pub async fn supports(&self, capability: Capability) -> Result<bool> {
+        Ok(self.capabilities_typed().await?.contains(&capability))
     }
 
     #[doc(alias = "Metadata")]
     /// The metadata for the sensor, which may include optional keys like
     /// `AttachImage`.
+    ///
+    /// Entries whose value is not a string are skipped, see
+    /// [`Sensor::metadata_raw`].
     pub async fn metadata(&self) -> Result<HashMap<String, String>> {
-        self.inner().get_property("Metadata").await
+        Ok(wire::string_metadata(self.metadata_raw().await?))
+    }
+
+    #[cfg(feature = "serde_json")]
+    #[doc(alias = "Metadata")]
+    /// The metadata for the sensor as a JSON object, see [`Sensor::metadata`].
+    pub async fn metadata_json(&self) -> Result<serde_json::Value> {
+        Ok(wire::metadata_json(self.metadata().await?))
+    }
+
+    #[doc(alias = "Metadata")]
+    /// The metadata for the sensor with the values as sent by the daemon.
+    pub async fn metadata_raw(&self) -> Result<HashMap<String, OwnedValue>> {
+        self.traced_property("Metadata").await
+    }
+
+    #[doc(alias = "AttachImage")]
+    /// The path of an image showing how to attach the sensor to the screen, if
+    /// any.
+    pub async fn attach_image(&self) -> Result<Option<PathBuf>> {
+        let mut metadata = self.metadata().await?;
+
+        Ok(metadata.remove(METADATA_ATTACH_IMAGE).map(PathBuf::from))
+    }
+}
+
+impl ProxyExt for Sensor<'_> {
+    fn proxy(&self) -> &zbus::Proxy<'_> {
+        &self.0
+    }
+
+    fn method_timeout(&self) -> Option<Duration> {
+        self.2.method_timeout
+    }
+
+    fn property_proxy(&self) -> Option<zbus::Proxy<'static>> {
+        self.3.proxy()
+    }
+}
+
+impl From<&Sensor<'_>> for OwnedObjectPath {
+    fn from(sensor: &Sensor<'_>) -> Self {
+        sensor.inner().path().to_owned().into()
     }
 }
 
@@ -227,3 +913,158 @@ impl<'a> Serialize for Sensor<'a> {
         ObjectPath::serialize(self.inner().path(), serializer)
     }
 }
+
+/// Sensors are compared by their object path.
+impl PartialEq for Sensor<'_> {
+    fn eq(&self, other: &Self) -> bool {
+        self.inner().path() == other.inner().path()
+    }
+}
+
+impl Eq for Sensor<'_> {}
+
+impl Hash for Sensor<'_> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.inner().path().hash(state);
+    }
+}
+
+/// Only the object path is printed, e.g.
+/// `Sensor("/org/freedesktop/ColorManager/...")`.
+impl std::fmt::Debug for Sensor<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_tuple("Sensor")
+            .field(&self.inner().path().as_str())
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn capability_bitfield() {
+        assert_eq!(
+            Capability::from_bitfield((1 << 1) | (1 << 6)),
+            vec![Capability::Lcd, Capability::Ambient]
+        );
+        assert_eq!(
+            Capability::from_bitfield((1 << 3) | (1 << 14)),
+            vec![Capability::Printer, Capability::WideGamutLcdRgbLed]
+        );
+        // Bits past `wide-gamut-lcd-rgb-led` are not known yet.
+        assert_eq!(Capability::from_bitfield(1 << 15), vec![]);
+    }
+
+    #[test]
+    fn sensor_options() {
+        let other = |key: &str| SensorOption::Other(key.to_owned());
+        let options = SensorOptions::new()
+            .set_str(other("string"), "abc")
+            .set_u32(other("integer"), 100)
+            .set_bool(other("boolean"), false)
+            .set_f64(SensorOption::SampleRed, 0.5);
+        let map = HashMap::from(options);
+        assert_eq!(map[&other("string")].value_signature(), "s");
+        assert_eq!(map[&other("integer")].value_signature(), "u");
+        assert_eq!(map[&other("boolean")].value_signature(), "b");
+        assert_eq!(map[&SensorOption::SampleRed], Value::from(0.5));
+
+        let options = SensorOptions::from(HashMap::from([(SensorOption::SampleBlue, 1.0)]));
+        assert_eq!(options.len(), 1);
+        assert!(SensorOptions::new().is_empty());
+    }
+
+    #[test]
+    fn sensor_option_check() {
+        assert!(SensorOption::SampleRed.check(&Value::from(0.5)).is_ok());
+        assert!(SensorOption::SampleBlue.check(&Value::from("0.5")).is_err());
+        let other = SensorOption::Other("remote-profile-hash".to_owned());
+        assert!(other.check(&Value::from("abc")).is_ok());
+    }
+
+    #[test]
+    fn xyz_reading() {
+        let reading = XyzReading::from_samples(&[(1.0, 2.0, 0.5), (3.0, 2.0, 0.5)]);
+        assert_eq!(reading.xyz, (2.0, 2.0, 0.5));
+        assert_eq!(reading.std_dev, (1.0, 0.0, 0.0));
+        assert_eq!(reading.samples, 2);
+
+        let reading = XyzReading::from_samples(&[(0.1, 0.2, 0.3)]);
+        assert_eq!(reading.xyz, (0.1, 0.2, 0.3));
+        assert_eq!(reading.std_dev, (0.0, 0.0, 0.0));
+    }
+
+    #[test]
+    fn lab_reading() {
+        let close = |a: f64, b: f64| (a - b).abs() < 0.01;
+
+        let white = XyzReading::D65.to_lab(XyzReading::D65);
+        assert!(close(white.l, 100.0) && close(white.a, 0.0) && close(white.b, 0.0));
+
+        // The sRGB red primary.
+        let red = XyzReading::from_xyz(0.4124, 0.2126, 0.0193).to_lab(XyzReading::D65);
+        assert!(close(red.l, 53.23), "{red:?}");
+        assert!(close(red.a, 80.11), "{red:?}");
+        assert!(close(red.b, 67.22), "{red:?}");
+
+        let black = XyzReading::from_xyz(0.0, 0.0, 0.0).to_lab(XyzReading::D50);
+        assert!(close(black.l, 0.0) && close(black.a, 0.0) && close(black.b, 0.0));
+    }
+
+    #[test]
+    fn delta_e() {
+        let lab = |l, a, b| LabReading { l, a, b };
+        let close = |a: f64, b: f64| (a - b).abs() < 0.0001;
+
+        let gray = lab(50.0, 0.0, 0.0);
+        assert_eq!(gray.delta_e_76(&gray), 0.0);
+        assert_eq!(gray.delta_e_76(&lab(50.0, 3.0, 4.0)), 5.0);
+        assert_eq!(gray.delta_e_2000(&gray), 0.0);
+
+        // From the test data of Sharma, Wu and Dalal, "The CIEDE2000
+        // color-difference formula".
+        let pairs = [
+            (
+                lab(50.0, 2.6772, -79.7751),
+                lab(50.0, 0.0, -82.7485),
+                2.0425,
+            ),
+            (lab(50.0, 0.0, 0.0), lab(50.0, -1.0, 2.0), 2.3669),
+            (lab(50.0, 2.5, 0.0), lab(73.0, 25.0, -18.0), 27.1492),
+            (lab(50.0, 2.5, 0.0), lab(50.0, 0.0, -2.5), 4.3065),
+            (
+                lab(60.2574, -34.0099, 36.2677),
+                lab(60.4626, -34.1751, 39.4387),
+                1.2644,
+            ),
+            (
+                lab(2.0776, 0.0795, -1.1350),
+                lab(0.9033, -0.0636, -0.5514),
+                0.9082,
+            ),
+        ];
+        for (first, second, expected) in pairs {
+            let delta_e = first.delta_e_2000(&second);
+            assert!(close(delta_e, expected), "{first:?} {second:?}: {delta_e}");
+            assert!(close(second.delta_e_2000(&first), expected));
+        }
+    }
+
+    #[test]
+    fn spectrum() {
+        let spectrum = Spectrum::from((380.0, 780.0, vec![1.0, 4.0, 2.0]));
+        assert_eq!(spectrum.normalized().values, [0.25, 1.0, 0.5]);
+        assert_eq!(spectrum.normalized().start, 380.0);
+
+        let dark = Spectrum::from((380.0, 780.0, vec![0.0, 0.0]));
+        assert_eq!(dark.normalized(), dark);
+
+        let other = Spectrum::from((380.0, 780.0, vec![3.0, 2.0, 4.0]));
+        let average = Spectrum::average(&[spectrum.clone(), other]).unwrap();
+        assert_eq!(average.values, [2.0, 3.0, 3.0]);
+
+        assert!(Spectrum::average(&[spectrum, dark]).is_err());
+    }
+}
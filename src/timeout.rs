@@ -0,0 +1,39 @@
+use std::{future::Future, io, pin::pin, time::Duration};
+
+use futures_util::future::{select, Either};
+
+/// Runs `future` to completion, failing with a [`io::ErrorKind::TimedOut`]
+/// error if it did not complete after `timeout`.
+pub(crate) async fn timeout<T>(
+    timeout: Duration,
+    future: impl Future<Output = zbus::Result<T>>,
+) -> zbus::Result<T> {
+    let timer = async_io::Timer::after(timeout);
+    match select(pin!(future), timer).await {
+        Either::Left((result, _)) => result,
+        Either::Right(_) => Err(zbus::Error::InputOutput(
+            io::Error::from(io::ErrorKind::TimedOut).into(),
+        )),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn timed_out() {
+        zbus::block_on(async {
+            let ready = timeout(Duration::from_secs(5), async { Ok(1) }).await;
+            assert_eq!(ready.unwrap(), 1);
+
+            let pending = std::future::pending::<zbus::Result<()>>();
+            match timeout(Duration::from_millis(10), pending).await {
+                Err(zbus::Error::InputOutput(err)) => {
+                    assert_eq!(err.kind(), io::ErrorKind::TimedOut)
+                }
+                other => panic!("unexpected result {other:?}"),
+            }
+        });
+    }
+}
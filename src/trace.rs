@@ -0,0 +1,95 @@
+use std::{sync::Arc, time::Duration};
+
+use zbus::{
+    zvariant::{DynamicType, OwnedValue},
+    Message, Proxy, Result,
+};
+
+/// Calls on a [`Proxy`] that are wrapped in a span recording the interface,
+/// member and object path when the `tracing` feature is enabled, and that fail
+/// once [`ProxyExt::method_timeout`] has elapsed.
+pub(crate) trait ProxyExt {
+    fn proxy(&self) -> &Proxy<'_>;
+
+    /// The proxy to read properties from instead of [`ProxyExt::proxy`], see
+    /// [`PropertyCache`](crate::config::PropertyCache).
+    fn property_proxy(&self) -> Option<Proxy<'static>> {
+        None
+    }
+
+    /// See [`ClientConfig::method_timeout`](crate::ClientConfig::method_timeout).
+    fn method_timeout(&self) -> Option<Duration> {
+        None
+    }
+
+    async fn traced_call<B>(&self, method_name: &'static str, body: &B) -> Result<Arc<Message>>
+    where
+        B: serde::ser::Serialize + DynamicType,
+    {
+        let proxy = self.proxy();
+        let call = proxy.call_method(method_name, body);
+
+        #[cfg(feature = "tracing")]
+        let call = traced(proxy, "call_method", method_name, call);
+
+        with_timeout(self.method_timeout(), call).await
+    }
+
+    async fn traced_property<T>(&self, property_name: &'static str) -> Result<T>
+    where
+        T: TryFrom<OwnedValue>,
+        T::Error: Into<zbus::Error>,
+    {
+        let refreshed = self.property_proxy();
+        let proxy = refreshed.as_ref().unwrap_or(self.proxy());
+        let get = proxy.get_property(property_name);
+
+        #[cfg(feature = "tracing")]
+        let get = traced(proxy, "get_property", property_name, get);
+
+        with_timeout(self.method_timeout(), get).await
+    }
+}
+
+impl ProxyExt for Proxy<'_> {
+    fn proxy(&self) -> &Proxy<'_> {
+        self
+    }
+}
+
+pub(crate) async fn with_timeout<T>(
+    timeout: Option<Duration>,
+    future: impl std::future::Future<Output = Result<T>>,
+) -> Result<T> {
+    match timeout {
+        Some(timeout) => crate::timeout::timeout(timeout, future).await,
+        None => future.await,
+    }
+}
+
+#[cfg(feature = "tracing")]
+async fn traced<T>(
+    proxy: &Proxy<'_>,
+    kind: &'static str,
+    member: &'static str,
+    future: impl std::future::Future<Output = Result<T>>,
+) -> Result<T> {
+    use tracing::Instrument;
+
+    let span = tracing::debug_span!(
+        "colord",
+        kind,
+        interface = %proxy.interface(),
+        member,
+        path = %proxy.path(),
+    );
+    async {
+        let result = future.await;
+        if let Err(err) = &result {
+            tracing::warn!(%err, "{kind} {member} failed");
+        }
+        result
+    }
+    .instrument(span)
+    .await
+}
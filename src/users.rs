@@ -0,0 +1,10 @@
+use nix::unistd::{Uid, User};
+
+/// Resolves the name of the user with the given `uid`, `None` if it cannot
+/// be resolved.
+pub(crate) fn user_name(uid: u32) -> Option<String> {
+    User::from_uid(Uid::from_raw(uid))
+        .ok()
+        .flatten()
+        .map(|user| user.name)
+}
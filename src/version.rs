@@ -0,0 +1,94 @@
+use std::{fmt, str::FromStr};
+
+/// The version of the running colord daemon, see
+/// [`ColorManager::daemon_version_typed`](crate::ColorManager::daemon_version_typed).
+///
+/// Versions compare by `major`, then `minor`, then `micro`, so predicates on
+/// the features of a daemon can be written as comparisons.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct DaemonVersion {
+    pub major: u32,
+    pub minor: u32,
+    pub micro: u32,
+}
+
+impl DaemonVersion {
+    /// The version of colord that added the `SystemVendor` and `SystemModel`
+    /// properties.
+    pub(crate) const SYSTEM_INFO: Self = Self::new(0, 1, 27);
+
+    pub const fn new(major: u32, minor: u32, micro: u32) -> Self {
+        Self {
+            major,
+            minor,
+            micro,
+        }
+    }
+
+    /// If the daemon is at least the given version.
+    pub fn at_least(&self, major: u32, minor: u32, micro: u32) -> bool {
+        *self >= Self::new(major, minor, micro)
+    }
+}
+
+impl fmt::Display for DaemonVersion {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}.{}.{}", self.major, self.minor, self.micro)
+    }
+}
+
+impl FromStr for DaemonVersion {
+    type Err = ParseDaemonVersionError;
+
+    /// Parses versions like `1.4.6`, a missing micro version is read as `0`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let err = || ParseDaemonVersionError(s.to_owned());
+
+        let mut parts = s.trim().split('.');
+        let mut next = |required: bool| match parts.next() {
+            Some(part) => part.parse::<u32>().map_err(|_| err()),
+            None if required => Err(err()),
+            None => Ok(0),
+        };
+        let version = Self::new(next(true)?, next(true)?, next(false)?);
+
+        if parts.next().is_some() {
+            return Err(err());
+        }
+
+        Ok(version)
+    }
+}
+
+/// The error returned when parsing a malformed [`DaemonVersion`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseDaemonVersionError(String);
+
+impl fmt::Display for ParseDaemonVersionError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Invalid daemon version `{}`", self.0)
+    }
+}
+
+impl std::error::Error for ParseDaemonVersionError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn daemon_version() {
+        let version = "1.4.6".parse::<DaemonVersion>().unwrap();
+        assert_eq!(version, DaemonVersion::new(1, 4, 6));
+        assert_eq!(version.to_string(), "1.4.6");
+        assert_eq!("1.4".parse(), Ok(DaemonVersion::new(1, 4, 0)));
+
+        assert!(version > DaemonVersion::new(1, 3, 10));
+        assert!(version.at_least(1, 4, 6));
+        assert!(!version.at_least(1, 4, 7));
+
+        for invalid in ["", "1", "1.x", "1.4.6.1", "v1.4.6"] {
+            assert!(invalid.parse::<DaemonVersion>().is_err(), "{invalid}");
+        }
+    }
+}
@@ -0,0 +1,105 @@
+//! Conversion of the enums that are sent as strings on the wire, and of the
+//! metadata dictionaries.
+//!
+//! The string of each variant is given by the serde attributes of the enum,
+//! usually `#[serde(rename_all = "lowercase")]`, so that serializing,
+//! deserializing and reading a property always agree.
+
+use std::collections::HashMap;
+
+use serde::de::{value::StrDeserializer, DeserializeOwned};
+use zbus::zvariant::OwnedValue;
+
+/// Parses `value` with the serde mapping of `T`, `None` if no variant
+/// matches.
+pub(crate) fn from_wire_str<T: DeserializeOwned>(value: &str) -> Option<T> {
+    T::deserialize(StrDeserializer::<serde::de::value::Error>::new(value)).ok()
+}
+
+/// Implements `From<OwnedValue>` for a wire enum, values that are not a known
+/// variant become `$fallback`.
+macro_rules! impl_from_owned_value {
+    ($ty:ty, $fallback:expr) => {
+        impl From<zbus::zvariant::OwnedValue> for $ty {
+            fn from(value: zbus::zvariant::OwnedValue) -> Self {
+                value
+                    .downcast_ref::<zbus::zvariant::Str>()
+                    .and_then(|value| crate::wire::from_wire_str(value.as_str()))
+                    .unwrap_or($fallback)
+            }
+        }
+    };
+}
+
+pub(crate) use impl_from_owned_value;
+
+/// Keeps the entries of a `Metadata` dictionary that hold a string, colord
+/// only sends strings but the values are variants on the wire.
+pub(crate) fn string_metadata(metadata: HashMap<String, OwnedValue>) -> HashMap<String, String> {
+    metadata
+        .into_iter()
+        .filter_map(|(key, value)| Some((key, String::try_from(value).ok()?)))
+        .collect()
+}
+
+#[cfg(feature = "serde_json")]
+/// Converts a `Metadata` dictionary into a JSON object.
+pub(crate) fn metadata_json(metadata: HashMap<String, String>) -> serde_json::Value {
+    serde_json::Value::Object(
+        metadata
+            .into_iter()
+            .map(|(key, value)| (key, serde_json::Value::String(value)))
+            .collect(),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use zbus::zvariant::{OwnedValue, Value};
+
+    use super::*;
+    use crate::{device, sensor, Scope};
+
+    #[test]
+    fn from_wire() {
+        assert_eq!(from_wire_str("temp"), Some(Scope::Temp));
+        assert_eq!(from_wire_str("webcam"), Some(device::Kind::Webcam));
+        assert_eq!(from_wire_str("busy"), Some(sensor::State::Busy));
+        assert_eq!(from_wire_str::<Scope>("Temp"), None);
+        assert_eq!(from_wire_str::<Scope>(""), None);
+    }
+
+    #[test]
+    fn metadata() {
+        let metadata = HashMap::from([
+            (
+                "XRANDR_name".to_owned(),
+                OwnedValue::from(Value::from("DP-1")),
+            ),
+            ("Scale".to_owned(), OwnedValue::from(Value::from(2u32))),
+        ]);
+        assert_eq!(
+            string_metadata(metadata),
+            HashMap::from([("XRANDR_name".to_owned(), "DP-1".to_owned())])
+        );
+    }
+
+    #[cfg(feature = "serde_json")]
+    #[test]
+    fn json() {
+        let metadata = HashMap::from([("XRANDR_name".to_owned(), "DP-1".to_owned())]);
+        assert_eq!(
+            metadata_json(metadata),
+            serde_json::json!({ "XRANDR_name": "DP-1" })
+        );
+    }
+
+    #[test]
+    fn fallback() {
+        let unknown = OwnedValue::from(Value::from("unexpected"));
+        assert_eq!(device::Mode::from(unknown), device::Mode::Unknown);
+
+        let not_a_string = OwnedValue::from(Value::from(1u32));
+        assert_eq!(Scope::from(not_a_string), Scope::Normal);
+    }
+}
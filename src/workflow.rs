@@ -0,0 +1,282 @@
+//! High level helpers that drive operations spanning several DBus objects.
+
+use std::{collections::HashMap, future::Future};
+
+use crate::{device::Relation, sensor::Capability, ColorManager, Device, Profile, Scope, Sensor};
+
+/// Inhibits profiling on a device for as long as it is alive.
+///
+/// Use [`ProfilingInhibitor::release`] to remove the inhibit and observe
+/// errors, otherwise the inhibit is removed in the background when the value
+/// is dropped.
+#[derive(Debug)]
+pub struct ProfilingInhibitor<'a> {
+    device: &'a Device<'a>,
+    released: bool,
+}
+
+impl<'a> ProfilingInhibitor<'a> {
+    #[doc(alias = "ProfilingInhibit")]
+    /// Adds an inhibit on all profiles for `device`.
+    pub async fn new(device: &'a Device<'a>) -> zbus::Result<ProfilingInhibitor<'a>> {
+        device.profiling_inhibit().await?;
+
+        Ok(Self {
+            device,
+            released: false,
+        })
+    }
+
+    #[doc(alias = "ProfilingUninhibit")]
+    /// Removes the inhibit on the device.
+    pub async fn release(mut self) -> zbus::Result<()> {
+        self.released = true;
+        self.device.profiling_uninhibit().await
+    }
+}
+
+impl<'a> Drop for ProfilingInhibitor<'a> {
+    fn drop(&mut self) {
+        if self.released {
+            return;
+        }
+        let connection = self.device.inner().connection().clone();
+        let path = self.device.inner().path().to_owned();
+        let config = self.device.shared_config();
+        let task = async move {
+            let device = Device::with_config(&connection, path, config).await?;
+            device.profiling_uninhibit().await
+        };
+        self.device
+            .inner()
+            .connection()
+            .executor()
+            .spawn(task, "ProfilingUninhibit")
+            .detach();
+    }
+}
+
+/// A device created with the `temp` scope that is deleted when dropped.
+///
+/// colord removes temporary devices once their client disconnects, this makes
+/// the cleanup deterministic for long-lived applications. Use
+/// [`TempDevice::delete`] to delete the device and observe errors, otherwise
+/// the device is deleted in the background when the value is dropped.
+#[derive(Debug)]
+pub struct TempDevice<'a> {
+    manager: &'a ColorManager<'a>,
+    device: Device<'a>,
+    deleted: bool,
+}
+
+impl<'a> TempDevice<'a> {
+    #[doc(alias = "CreateDevice")]
+    /// Creates the device `device_id` with [`Scope::Temp`], see
+    /// [`ColorManager::create_device_with_id`].
+    pub async fn new(
+        manager: &'a ColorManager<'a>,
+        device_id: &str,
+        properties: HashMap<&str, &str>,
+    ) -> zbus::Result<TempDevice<'a>> {
+        let device = manager
+            .create_device_with_id(device_id, Scope::Temp, properties)
+            .await?;
+
+        Ok(Self {
+            manager,
+            device,
+            deleted: false,
+        })
+    }
+
+    /// The created device.
+    pub fn device(&self) -> &Device<'a> {
+        &self.device
+    }
+
+    #[doc(alias = "DeleteDevice")]
+    /// Deletes the device.
+    pub async fn delete(mut self) -> crate::Result<()> {
+        self.deleted = true;
+        self.manager.delete_device(&self.device).await
+    }
+}
+
+impl<'a> Drop for TempDevice<'a> {
+    fn drop(&mut self) {
+        if self.deleted {
+            return;
+        }
+        let connection = self.device.inner().connection().clone();
+        let path = self.device.inner().path().to_owned();
+        let config = self.manager.shared_config();
+        let task = async move {
+            let manager = ColorManager::with_config(&connection, config.clone()).await?;
+            let device = Device::with_config(&connection, path, config).await?;
+            manager.delete_device(&device).await
+        };
+        self.device
+            .inner()
+            .connection()
+            .executor()
+            .spawn(task, "DeleteDevice")
+            .detach();
+    }
+}
+
+/// Locks a sensor for as long as it is alive.
+///
+/// Use [`SensorLock::release`] to unlock the sensor and observe errors,
+/// otherwise the sensor is unlocked in the background when the value is
+/// dropped.
+#[derive(Debug)]
+pub struct SensorLock<'a> {
+    sensor: &'a Sensor<'a>,
+    released: bool,
+}
+
+impl<'a> SensorLock<'a> {
+    #[doc(alias = "Lock")]
+    /// Locks `sensor` for use by this application.
+    pub async fn new(sensor: &'a Sensor<'a>) -> zbus::Result<SensorLock<'a>> {
+        sensor.lock().await?;
+
+        Ok(Self {
+            sensor,
+            released: false,
+        })
+    }
+
+    #[doc(alias = "Unlock")]
+    /// Unlocks the sensor.
+    pub async fn release(mut self) -> zbus::Result<()> {
+        self.released = true;
+        self.sensor.unlock().await
+    }
+}
+
+impl<'a> Drop for SensorLock<'a> {
+    fn drop(&mut self) {
+        if self.released {
+            return;
+        }
+        self.sensor.forget_lock();
+        let connection = self.sensor.inner().connection().clone();
+        let path = self.sensor.inner().path().to_owned();
+        let config = self.sensor.shared_config();
+        let task = async move {
+            let sensor = Sensor::with_config(&connection, path, config).await?;
+            sensor.unlock().await
+        };
+        self.sensor
+            .inner()
+            .connection()
+            .executor()
+            .spawn(task, "Unlock")
+            .detach();
+    }
+}
+
+/// The application specific steps of a [`DisplayCalibration`].
+///
+/// Only displaying the color patches and building the ICC profile out of the
+/// samples have to be provided, the other steps have default implementations
+/// that can be overridden.
+pub trait CalibrationSteps {
+    /// Displays the color patch `index`, returns `false` once there are no
+    /// more patches to measure.
+    fn display_patch(&mut self, index: usize) -> impl Future<Output = crate::Result<bool>>;
+
+    /// Creates a profile out of the measured samples, e.g. by generating an
+    /// ICC file and calling [`ColorManager::create_profile_from_file`].
+    fn create_profile<'m>(
+        &mut self,
+        manager: &'m ColorManager<'_>,
+        samples: &[(f64, f64, f64)],
+    ) -> impl Future<Output = crate::Result<Profile<'m>>>;
+
+    /// Takes a sample of the currently displayed patch.
+    fn sample(
+        &mut self,
+        sensor: &Sensor<'_>,
+        capability: Capability,
+    ) -> impl Future<Output = crate::Result<(f64, f64, f64)>> {
+        async move { Ok(sensor.sample(capability).await?) }
+    }
+
+    /// Adds the created profile to the calibrated device.
+    fn add_profile(
+        &mut self,
+        device: &Device<'_>,
+        relation: Relation,
+        profile: &Profile<'_>,
+    ) -> impl Future<Output = crate::Result<()>> {
+        async move { Ok(device.add_profile(relation, profile).await?) }
+    }
+}
+
+/// Drives the calibration of a display.
+///
+/// Profiling is inhibited on the device and the sensor is locked while the
+/// patches are measured, both are released once the profile has been added to
+/// the device or an error happened.
+#[derive(Debug)]
+pub struct DisplayCalibration<'a> {
+    manager: &'a ColorManager<'a>,
+    device: &'a Device<'a>,
+    sensor: &'a Sensor<'a>,
+    capability: Capability,
+    relation: Relation,
+}
+
+impl<'a> DisplayCalibration<'a> {
+    /// Creates a calibration of `device` measured with `sensor`.
+    ///
+    /// By default the sensor is used with [`Capability::Lcd`] and the profile
+    /// is added with [`Relation::Hard`].
+    pub fn new(
+        manager: &'a ColorManager<'a>,
+        device: &'a Device<'a>,
+        sensor: &'a Sensor<'a>,
+    ) -> Self {
+        Self {
+            manager,
+            device,
+            sensor,
+            capability: Capability::Lcd,
+            relation: Relation::Hard,
+        }
+    }
+
+    /// Sets the capability used to take samples.
+    pub fn capability(mut self, capability: Capability) -> Self {
+        self.capability = capability;
+        self
+    }
+
+    /// Sets the relation used to add the created profile to the device.
+    pub fn relation(mut self, relation: Relation) -> Self {
+        self.relation = relation;
+        self
+    }
+
+    /// Runs the calibration and returns the created profile.
+    pub async fn run<S: CalibrationSteps>(&self, steps: &mut S) -> crate::Result<Profile<'a>> {
+        let inhibitor = ProfilingInhibitor::new(self.device).await?;
+        let lock = SensorLock::new(self.sensor).await?;
+
+        let mut samples = Vec::new();
+        while steps.display_patch(samples.len()).await? {
+            samples.push(steps.sample(self.sensor, self.capability).await?);
+        }
+        let profile = steps.create_profile(self.manager, &samples).await?;
+        steps
+            .add_profile(self.device, self.relation.clone(), &profile)
+            .await?;
+
+        inhibitor.release().await?;
+        lock.release().await?;
+
+        Ok(profile)
+    }
+}
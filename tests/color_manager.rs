@@ -0,0 +1,933 @@
+mod mock;
+
+use std::{
+    collections::HashMap,
+    io::{Read, Seek},
+    os::unix::io::AsRawFd,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    },
+    time::Duration,
+};
+
+use color_manager::{
+    cache::ProfileCache,
+    collections,
+    device::{self, Kind, Relation},
+    profile::{ValidationIssue, Warning},
+    qualifier::QualifierQuery,
+    sensor::State,
+    workflow::TempDevice,
+    Change, ClientConfig, ColorManager, DaemonVersion, Error, Scope,
+};
+use futures_util::{future::join, StreamExt, TryStreamExt};
+use zbus::zvariant::OwnedObjectPath;
+
+#[test]
+fn devices() {
+    zbus::block_on(async {
+        let (manager, server) = mock::manager().await;
+        assert!(manager.devices().await.unwrap().is_empty());
+
+        mock::add_device(&server, "xrandr-Foo", &[("Model", "Foo")]).await;
+
+        let devices = manager.devices().await.unwrap();
+        assert_eq!(devices.len(), 1);
+        assert_eq!(devices[0].device_id().await.unwrap(), "xrandr-Foo");
+    });
+}
+
+#[test]
+fn devices_diff() {
+    zbus::block_on(async {
+        let (manager, server) = mock::manager().await;
+        mock::add_device(&server, "xrandr-Foo", &[]).await;
+        let old = manager.devices().await.unwrap();
+
+        let path = mock::add_device(&server, "xrandr-Bar", &[]).await;
+        let new = manager.devices().await.unwrap();
+
+        let (added, removed) = device::diff(&old, &new);
+        assert_eq!(added.len(), 1);
+        assert_eq!(added[0].inner().path(), &*path);
+        assert!(removed.is_empty());
+
+        let (added, removed) = device::diff(&new, &old);
+        assert!(added.is_empty());
+        assert_eq!(removed.len(), 1);
+        assert_eq!(removed[0].inner().path(), &*path);
+    });
+}
+
+#[test]
+fn replace_or_push() {
+    zbus::block_on(async {
+        let (manager, server) = mock::manager().await;
+        mock::add_device(&server, "xrandr-Foo", &[]).await;
+        let mut devices = manager.devices().await.unwrap();
+
+        let foo = manager.find_device_by_id("xrandr-Foo").await.unwrap();
+        assert!(foo.matches_path(devices[0].inner().path()));
+        assert_eq!(
+            collections::replace_or_push(&mut devices, foo.clone()),
+            Some(foo)
+        );
+        assert_eq!(devices.len(), 1);
+
+        let path = mock::add_device(&server, "xrandr-Bar", &[]).await;
+        let bar = manager.find_device_by_id("xrandr-Bar").await.unwrap();
+        assert!(collections::replace_or_push(&mut devices, bar).is_none());
+        assert!(devices[1].matches_path(&path));
+    });
+}
+
+#[test]
+fn devices_by_kind_stream() {
+    zbus::block_on(async {
+        let (manager, server) = mock::manager().await;
+        mock::add_device(&server, "xrandr-Foo", &[("Kind", "display")]).await;
+        let path = mock::add_device(&server, "cups-Bar", &[("Kind", "printer")]).await;
+
+        let printers = manager
+            .devices_by_kind_stream(Kind::Printer)
+            .await
+            .unwrap()
+            .try_collect::<Vec<_>>()
+            .await
+            .unwrap();
+        assert_eq!(printers.len(), 1);
+        assert!(printers[0].matches_path(&path));
+    });
+}
+
+#[test]
+fn devices_for_seat() {
+    zbus::block_on(async {
+        let (manager, server) = mock::manager().await;
+        mock::add_device(&server, "xrandr-Foo", &[("Seat", "seat0")]).await;
+        let path = mock::add_device(&server, "xrandr-Bar", &[("Seat", "seat1")]).await;
+
+        let devices = manager.devices_for_seat("seat1").await.unwrap();
+        assert_eq!(devices.len(), 1);
+        assert_eq!(devices[0].inner().path(), &*path);
+        assert!(manager.devices_for_seat("seat2").await.unwrap().is_empty());
+        assert_eq!(devices[0].seat().await.unwrap().as_deref(), Some("seat1"));
+        assert_eq!(
+            format!("{:?}", devices[0]),
+            format!("Device({:?})", path.as_str())
+        );
+
+        mock::add_device(&server, "xrandr-Baz", &[]).await;
+        let device = manager.find_device_by_id("xrandr-Baz").await.unwrap();
+        assert_eq!(device.seat().await.unwrap(), None);
+    });
+}
+
+#[test]
+fn find_device_by_id() {
+    zbus::block_on(async {
+        let (manager, server) = mock::manager().await;
+        let path = mock::add_device(&server, "xrandr-Foo", &[]).await;
+
+        let device = manager.find_device_by_id("xrandr-Foo").await.unwrap();
+        assert_eq!(device.inner().path(), &*path);
+        assert_eq!(OwnedObjectPath::from(&device), path);
+
+        assert!(manager
+            .try_find_device_by_id("xrandr-Bar")
+            .await
+            .unwrap()
+            .is_none());
+        assert!(matches!(
+            manager.delete_device_by_id("xrandr-Bar").await,
+            Err(Error::NotFound(_))
+        ));
+
+        let sibling = ColorManager::from_device(&device).await.unwrap();
+        assert_eq!(sibling.devices().await.unwrap(), [device]);
+    });
+}
+
+#[test]
+fn device_added() {
+    zbus::block_on(async {
+        let (manager, server) = mock::manager().await;
+        // Subscribed before the device is added, so the signal is not missed.
+        let mut changes = std::pin::pin!(manager.changes().await.unwrap());
+
+        let path = mock::add_device(&server, "xrandr-Foo", &[]).await;
+
+        assert_eq!(
+            changes.next().await.unwrap().unwrap(),
+            Change::DeviceAdded(path.clone())
+        );
+        let device = manager.find_device_by_id("xrandr-Foo").await.unwrap();
+        assert_eq!(device.inner().path(), &*path);
+    });
+}
+
+#[test]
+fn changes() {
+    zbus::block_on(async {
+        let (manager, server) = mock::manager().await;
+        let mut changes = std::pin::pin!(manager.changes().await.unwrap());
+
+        // Both changes are kept until the stream is polled.
+        let foo = mock::add_device(&server, "xrandr-Foo", &[]).await;
+        let bar = mock::add_device(&server, "xrandr-Bar", &[]).await;
+        assert_eq!(
+            changes.next().await.unwrap().unwrap(),
+            Change::DeviceAdded(foo)
+        );
+        assert_eq!(
+            changes.next().await.unwrap().unwrap(),
+            Change::DeviceAdded(bar)
+        );
+    });
+}
+
+#[test]
+fn on_change() {
+    zbus::block_on(async {
+        let (manager, server) = mock::manager().await;
+        let count = Arc::new(AtomicUsize::new(0));
+        let subscription = manager
+            .on_change({
+                let count = count.clone();
+                move || {
+                    count.fetch_add(1, Ordering::SeqCst);
+                }
+            })
+            .await
+            .unwrap();
+
+        mock::add_device(&server, "xrandr-Foo", &[]).await;
+        for _ in 0..500 {
+            if count.load(Ordering::SeqCst) > 0 {
+                break;
+            }
+            async_io::Timer::after(Duration::from_millis(10)).await;
+        }
+        assert_eq!(count.load(Ordering::SeqCst), 1);
+
+        drop(subscription);
+        mock::add_device(&server, "xrandr-Bar", &[]).await;
+        async_io::Timer::after(Duration::from_millis(100)).await;
+        assert_eq!(count.load(Ordering::SeqCst), 1);
+    });
+}
+
+#[test]
+fn properties() {
+    zbus::block_on(async {
+        let (manager, server) = mock::manager().await;
+        assert_eq!(manager.daemon_version().await.unwrap(), "1.4.6");
+
+        mock::add_device(
+            &server,
+            "xrandr-Foo",
+            &[("Kind", "display"), ("XRANDR_name", "DP-1")],
+        )
+        .await;
+        let device = manager.find_device_by_id("xrandr-Foo").await.unwrap();
+        assert_eq!(device.kind().await.unwrap(), Kind::Display);
+        assert_eq!(device.metadata().await.unwrap()["XRANDR_name"], "DP-1");
+        let metadata = device.metadata_raw().await.unwrap();
+        assert_eq!(metadata["XRANDR_name"].downcast_ref::<str>(), Some("DP-1"));
+        assert!(device.enabled().await.unwrap());
+        assert!(device.is_owned_by(0).await.unwrap());
+        assert!(!device.is_owned_by(1000).await.unwrap());
+        // The mock reports every device as created by root.
+        let mine = ColorManager::current_uid() == 0;
+        assert_eq!(device.is_mine().await.unwrap(), mine);
+        assert_eq!(manager.my_devices().await.unwrap().len(), usize::from(mine));
+
+        device.set_model("Foo").await.unwrap();
+        device.set_vendor("Bar").await.unwrap();
+        device.set_kind(Kind::Printer).await.unwrap();
+        assert_eq!(device.model().await.unwrap(), "Foo");
+        assert_eq!(device.vendor().await.unwrap(), "Bar");
+        assert_eq!(device.kind().await.unwrap(), Kind::Printer);
+
+        device.set_enabled(false).await.unwrap();
+        assert!(!device.enabled().await.unwrap());
+
+        let reloaded = device.clone().reload().await.unwrap();
+        assert_eq!(reloaded, device);
+        assert_eq!(reloaded.model().await.unwrap(), "Foo");
+    });
+}
+
+#[test]
+fn sensor_lock() {
+    zbus::block_on(async {
+        let (manager, server) = mock::manager().await;
+        mock::add_sensor(&server, "dummy").await;
+
+        let sensor = manager.find_sensor_by_id("dummy").await.unwrap();
+        let clone = sensor.clone();
+        assert!(!sensor.held_by_self());
+
+        sensor.lock().await.unwrap();
+        assert!(sensor.locked().await.unwrap());
+        assert!(sensor.held_by_self());
+        assert!(clone.held_by_self());
+
+        let other = manager.find_sensor_by_id("dummy").await.unwrap();
+        assert!(other.locked().await.unwrap());
+        assert!(!other.held_by_self());
+
+        clone.unlock().await.unwrap();
+        assert!(!sensor.locked().await.unwrap());
+        assert!(!sensor.held_by_self());
+    });
+}
+
+#[test]
+fn wait_for_state() {
+    zbus::block_on(async {
+        let (manager, server) = mock::manager().await;
+        mock::add_sensor(&server, "dummy").await;
+        let sensor = manager.find_sensor_by_id("dummy").await.unwrap();
+        sensor.lock().await.unwrap();
+
+        let timed_out = sensor
+            .wait_for_state(State::Idle, Duration::from_millis(50))
+            .await;
+        assert!(matches!(timed_out, Err(zbus::Error::InputOutput(_))));
+
+        let unlock = async {
+            async_io::Timer::after(Duration::from_millis(100)).await;
+            sensor.unlock().await
+        };
+        let (waited, unlocked) = join(
+            sensor.wait_for_state(State::Idle, Duration::from_secs(5)),
+            unlock,
+        )
+        .await;
+        unlocked.unwrap();
+        waited.unwrap();
+    });
+}
+
+#[test]
+fn sensors_with_state() {
+    zbus::block_on(async {
+        let (manager, server) = mock::manager().await;
+        mock::add_sensor(&server, "dummy").await;
+        mock::add_sensor(&server, "other").await;
+
+        let sensor = manager.find_sensor_by_id("other").await.unwrap();
+        sensor.lock().await.unwrap();
+
+        let summaries = manager.sensors_with_state().await.unwrap();
+        assert_eq!(summaries.len(), 2);
+        assert_eq!(summaries[0].model, "Dummy Sensor");
+        assert_eq!(summaries[0].vendor, "Acme Corp.");
+        assert_eq!(summaries[0].state, State::Idle);
+        assert_eq!(summaries[1].state, State::Busy);
+    });
+}
+
+#[test]
+fn native_sensors() {
+    zbus::block_on(async {
+        let (manager, server) = mock::manager().await;
+        mock::add_sensor(&server, "dummy").await;
+        mock::add_sensor_with_driver(&server, "argyll", false).await;
+
+        let native = manager.native_sensors().await.unwrap();
+        assert_eq!(native.len(), 1);
+        assert_eq!(native[0], manager.find_sensor_by_id("dummy").await.unwrap());
+
+        // The mock sensors do not have a serial number.
+        assert_eq!(native[0].serial().await.unwrap(), None);
+    });
+}
+
+#[test]
+fn sensor_calibration() {
+    zbus::block_on(async {
+        let (manager, server) = mock::manager().await;
+        mock::add_sensor(&server, "dummy").await;
+        mock::add_sensor_with_driver(&server, "argyll", false).await;
+        let dummy = manager.find_sensor_by_id("dummy").await.unwrap();
+        let argyll = manager.find_sensor_by_id("argyll").await.unwrap();
+
+        assert!(dummy.needs_calibration().await.unwrap());
+        dummy.calibrate().await.unwrap();
+
+        assert!(!argyll.needs_calibration().await.unwrap());
+        assert!(matches!(
+            argyll.calibrate().await,
+            Err(zbus::Error::Failure(_))
+        ));
+    });
+}
+
+#[test]
+fn profiling_inhibit() {
+    zbus::block_on(async {
+        let (manager, server) = mock::manager().await;
+        mock::add_device(&server, "xrandr-Foo", &[]).await;
+        let device = manager.find_device_by_id("xrandr-Foo").await.unwrap();
+        assert!(!device.is_profiling_inhibited().await.unwrap());
+
+        device.profiling_inhibit().await.unwrap();
+        assert!(device.is_profiling_inhibited().await.unwrap());
+
+        device.profiling_uninhibit().await.unwrap();
+        assert!(!device.is_profiling_inhibited().await.unwrap());
+    });
+}
+
+#[test]
+fn can_be_profiled() {
+    zbus::block_on(async {
+        let (manager, server) = mock::manager().await;
+        mock::add_device(&server, "xrandr-Foo", &[("Kind", "display")]).await;
+        mock::add_device(&server, "sane-Bar", &[("Kind", "scanner")]).await;
+        let display = manager.find_device_by_id("xrandr-Foo").await.unwrap();
+        let scanner = manager.find_device_by_id("sane-Bar").await.unwrap();
+        assert!(display.can_be_profiled().await.unwrap());
+        assert!(!scanner.can_be_profiled().await.unwrap());
+        mock::add_device(&server, "xrandr-Baz", &[("Kind", "unknown")]).await;
+        let unknown = manager.find_device_by_id("xrandr-Baz").await.unwrap();
+        assert!(!unknown.can_be_profiled().await.unwrap());
+
+        display.profiling_inhibit().await.unwrap();
+        assert!(!display.can_be_profiled().await.unwrap());
+        display.profiling_uninhibit().await.unwrap();
+
+        display.set_enabled(false).await.unwrap();
+        assert!(!display.can_be_profiled().await.unwrap());
+    });
+}
+
+#[test]
+fn profile_cache() {
+    zbus::block_on(async {
+        let (manager, server) = mock::manager().await;
+        let path = mock::add_profile(&server, "icc-Foo", &[("Title", "Foo")]).await;
+        let profile = manager.profiles().await.unwrap().remove(0);
+
+        let mut cache = ProfileCache::default();
+        assert!(cache.cached(&path).is_none());
+        assert_eq!(cache.get(&profile).await.unwrap().title, "Foo");
+        assert_eq!(cache.cached(&path).unwrap().profile_id, "icc-Foo");
+
+        profile.set_property("Title", "Bar").await.unwrap();
+        assert_eq!(cache.get(&profile).await.unwrap().title, "Foo");
+
+        cache.invalidate(&Change::DeviceChanged(path.clone()));
+        assert!(cache.cached(&path).is_some());
+        cache.invalidate(&Change::ProfileChanged(path.clone()));
+        assert!(cache.cached(&path).is_none());
+        assert_eq!(cache.get(&profile).await.unwrap().title, "Bar");
+    });
+}
+
+#[test]
+fn system_info() {
+    zbus::block_on(async {
+        let (manager, _server) = mock::manager().await;
+
+        let info = manager.system_info().await.unwrap();
+        assert_eq!(info.daemon_version, "1.4.6");
+        assert_eq!(info.vendor.as_deref(), Some("Acme Corp."));
+        assert_eq!(info.model.as_deref(), Some("Anvil 3000"));
+
+        let version = manager.daemon_version_typed().await.unwrap();
+        assert_eq!(version, DaemonVersion::new(1, 4, 6));
+        let vendor = manager.system_vendor().await.unwrap();
+        assert_eq!(vendor.as_deref(), Some("Acme Corp."));
+    });
+}
+
+#[test]
+fn old_daemon() {
+    zbus::block_on(async {
+        let (manager, _server) = mock::old_manager().await;
+
+        let version = manager.daemon_version_typed().await.unwrap();
+        assert_eq!(version, DaemonVersion::new(0, 1, 20));
+        assert_eq!(manager.system_vendor().await.unwrap(), None);
+        assert_eq!(manager.system_model().await.unwrap(), None);
+
+        let info = manager.system_info().await.unwrap();
+        assert_eq!(info.daemon_version, "0.1.20");
+        assert_eq!(info.vendor, None);
+        assert_eq!(info.model, None);
+    });
+}
+
+#[test]
+fn set_enabled_and_wait() {
+    zbus::block_on(async {
+        let (manager, server) = mock::manager().await;
+        mock::add_device(&server, "xrandr-Foo", &[]).await;
+        let device = manager.find_device_by_id("xrandr-Foo").await.unwrap();
+
+        device
+            .set_enabled_and_wait(false, Duration::from_secs(5))
+            .await
+            .unwrap();
+        assert!(!device.enabled().await.unwrap());
+
+        // Already disabled, nothing to wait for.
+        device
+            .set_enabled_and_wait(false, Duration::from_millis(100))
+            .await
+            .unwrap();
+        assert!(!device.enabled().await.unwrap());
+    });
+}
+
+#[test]
+fn user_profiles() {
+    zbus::block_on(async {
+        let (manager, server) = mock::manager().await;
+        mock::add_profile(&server, "icc-System", &[("IsSystemWide", "true")]).await;
+        let path = mock::add_profile(&server, "icc-User", &[]).await;
+
+        let profiles = manager.user_profiles().await.unwrap();
+        assert_eq!(profiles.len(), 1);
+        assert_eq!(profiles[0].inner().path(), &*path);
+    });
+}
+
+#[test]
+fn orphan_profiles() {
+    zbus::block_on(async {
+        let (manager, server) = mock::manager().await;
+        mock::add_device(&server, "xrandr-Foo", &[]).await;
+        mock::add_profile(&server, "icc-Attached", &[]).await;
+        let path = mock::add_profile(&server, "icc-Orphan", &[]).await;
+        let device = manager.find_device_by_id("xrandr-Foo").await.unwrap();
+        let attached = manager.find_profile_by_id("icc-Attached").await.unwrap();
+        device.add_profile(Relation::Hard, &attached).await.unwrap();
+
+        let orphans = manager.orphan_profiles().await.unwrap();
+        assert_eq!(orphans.len(), 1);
+        assert_eq!(orphans[0].inner().path(), &*path);
+    });
+}
+
+#[test]
+fn create_display_from_edid() {
+    zbus::block_on(async {
+        let (manager, _server) = mock::manager().await;
+
+        let mut edid = vec![0u8; 128];
+        edid[..8].copy_from_slice(&[0x00, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0x00]);
+        // `DEL`, product 0xa0c3, serial number 42.
+        edid[8..10].copy_from_slice(&[0x10, 0xAC]);
+        edid[10..12].copy_from_slice(&[0xC3, 0xA0]);
+        edid[12..16].copy_from_slice(&42u32.to_le_bytes());
+        edid[57] = 0xFC;
+        edid[59..72].copy_from_slice(b"DELL U2415\n  ");
+        let sum = edid.iter().fold(0u8, |sum, byte| sum.wrapping_add(*byte));
+        edid[127] = 0u8.wrapping_sub(sum);
+
+        let device = manager
+            .create_display_from_edid(&edid, Scope::Temp)
+            .await
+            .unwrap();
+        assert_eq!(
+            device.device_id().await.unwrap(),
+            "xrandr-DEL-DELL U2415-42"
+        );
+        assert_eq!(device.kind().await.unwrap(), Kind::Display);
+        assert_eq!(device.vendor().await.unwrap(), "DEL");
+        assert_eq!(device.model().await.unwrap(), "DELL U2415");
+        assert_eq!(device.serial().await.unwrap(), "42");
+        assert_eq!(manager.devices().await.unwrap(), [device]);
+
+        edid[127] = edid[127].wrapping_add(1);
+        assert!(matches!(
+            manager.create_display_from_edid(&edid, Scope::Temp).await,
+            Err(Error::InvalidEdid(_))
+        ));
+    });
+}
+
+#[test]
+fn temp_device() {
+    zbus::block_on(async {
+        let (manager, _server) = mock::manager().await;
+
+        let temp = TempDevice::new(
+            &manager,
+            "xrandr-Temp",
+            HashMap::from([("Kind", "display")]),
+        )
+        .await
+        .unwrap();
+        assert_eq!(temp.device().device_id().await.unwrap(), "xrandr-Temp");
+        assert_eq!(manager.devices().await.unwrap(), [temp.device().clone()]);
+
+        // The device is deleted in the background once dropped.
+        drop(temp);
+        for _ in 0..50 {
+            if manager.devices().await.unwrap().is_empty() {
+                return;
+            }
+            async_io::Timer::after(Duration::from_millis(10)).await;
+        }
+        panic!("the temporary device was not deleted");
+    });
+}
+
+#[test]
+fn delete() {
+    zbus::block_on(async {
+        let (manager, server) = mock::manager().await;
+        mock::add_device(&server, "xrandr-Foo", &[]).await;
+        mock::add_profile(&server, "icc-System", &[("IsSystemWide", "true")]).await;
+        mock::add_profile(&server, "icc-User", &[]).await;
+
+        let device = manager.find_device_by_id("xrandr-Foo").await.unwrap();
+        manager.delete_device(&device).await.unwrap();
+        assert!(matches!(
+            manager.delete_device(&device).await,
+            Err(Error::NotFound(_))
+        ));
+
+        let system = manager.find_profile_by_id("icc-System").await.unwrap();
+        assert!(matches!(
+            manager.delete_profile(&system).await,
+            Err(Error::PermissionDenied(_))
+        ));
+        let user = manager.find_profile_by_id("icc-User").await.unwrap();
+        manager.delete_profile(&user).await.unwrap();
+        assert!(matches!(
+            manager.delete_profile(&user).await,
+            Err(Error::NotFound(_))
+        ));
+    });
+}
+
+#[test]
+fn find_profile_by_filename() {
+    zbus::block_on(async {
+        let (manager, server) = mock::manager().await;
+        let path = mock::add_profile(
+            &server,
+            "icc-Foo",
+            &[("Filename", "/usr/share/color/icc/foo.icc")],
+        )
+        .await;
+
+        let profile = manager
+            .try_find_profile_by_filename("foo.icc")
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(profile.inner().path(), &*path);
+        assert!(manager
+            .try_find_profile_by_filename("/usr/share/color/icc/bar.icc")
+            .await
+            .unwrap()
+            .is_none());
+    });
+}
+
+#[test]
+fn validate_profile() {
+    zbus::block_on(async {
+        let (manager, server) = mock::manager().await;
+        let properties = [
+            ("Kind", "display-device"),
+            ("Colorspace", "cmyk"),
+            ("Warnings", "description-missing"),
+        ];
+        mock::add_profile(&server, "icc-Cmyk", &properties).await;
+        let properties = [("Kind", "display-device"), ("Colorspace", "rgb")];
+        mock::add_profile(&server, "icc-Rgb", &properties).await;
+
+        let cmyk = manager.find_profile_by_id("icc-Cmyk").await.unwrap();
+        assert_eq!(
+            cmyk.validate().await.unwrap(),
+            [
+                ValidationIssue::Daemon(Warning::DescriptionMissing),
+                ValidationIssue::ColorspaceMismatch {
+                    kind: "display-device".to_owned(),
+                    colorspace: "cmyk".to_owned(),
+                },
+            ]
+        );
+        let rgb = manager.find_profile_by_id("icc-Rgb").await.unwrap();
+        assert!(rgb.validate().await.unwrap().is_empty());
+    });
+}
+
+#[test]
+fn wait_for_profile() {
+    zbus::block_on(async {
+        let (manager, server) = mock::manager().await;
+        mock::add_device(&server, "xrandr-Foo", &[]).await;
+        mock::add_profile(&server, "icc-Foo", &[]).await;
+        let device = manager.find_device_by_id("xrandr-Foo").await.unwrap();
+        let profile = manager.find_profile_by_id("icc-Foo").await.unwrap();
+
+        let timed_out = device
+            .wait_for_profile(&profile, Duration::from_millis(50))
+            .await;
+        assert!(matches!(timed_out, Err(zbus::Error::InputOutput(_))));
+
+        let add = async {
+            async_io::Timer::after(Duration::from_millis(100)).await;
+            device.add_profile(Relation::Hard, &profile).await
+        };
+        let (waited, added) = join(
+            device.wait_for_profile(&profile, Duration::from_secs(5)),
+            add,
+        )
+        .await;
+        added.unwrap();
+        waited.unwrap();
+
+        device
+            .remove_profile_and_wait(&profile, Duration::from_secs(5))
+            .await
+            .unwrap();
+        assert!(device.profiles().await.unwrap().is_empty());
+        assert!(device
+            .remove_profile_and_wait(&profile, Duration::from_secs(5))
+            .await
+            .is_err());
+    });
+}
+
+#[test]
+fn profile_relations() {
+    zbus::block_on(async {
+        let (manager, server) = mock::manager().await;
+        mock::add_device(&server, "xrandr-Foo", &[]).await;
+        mock::add_profile(&server, "icc-Foo", &[]).await;
+        mock::add_profile(&server, "icc-Bar", &[]).await;
+        let device = manager.find_device_by_id("xrandr-Foo").await.unwrap();
+        let foo = manager.find_profile_by_id("icc-Foo").await.unwrap();
+        let bar = manager.find_profile_by_id("icc-Bar").await.unwrap();
+
+        device.add_profile(Relation::Soft, &foo).await.unwrap();
+        device.add_profile(Relation::Hard, &bar).await.unwrap();
+
+        let relations = device.profile_relations().await.unwrap();
+        assert_eq!(
+            relations,
+            [(foo.clone(), Relation::Soft), (bar.clone(), Relation::Hard)]
+        );
+
+        let qualifiers = vec!["RGB".to_owned(), "*".to_owned(), "*".to_owned()];
+        let profile = device
+            .profile_for_qualifiers_iter(qualifiers)
+            .await
+            .unwrap();
+        assert_eq!(profile, foo);
+        let query = QualifierQuery::new().exact("RGB").unwrap().any().any();
+        assert_eq!(device.profile_for_query(&query).await.unwrap(), foo);
+        assert!(foo.is_default_for(&device).await.unwrap());
+        assert!(!bar.is_default_for(&device).await.unwrap());
+
+        let (hard, soft) = device.profiles_by_relation().await.unwrap();
+        assert_eq!(hard, [bar]);
+        assert_eq!(soft, [foo]);
+    });
+}
+
+#[test]
+fn client_config() {
+    zbus::block_on(async {
+        let (manager, server) = mock::manager().await;
+        mock::add_device(&server, "xrandr-Foo", &[]).await;
+        let config = ClientConfig {
+            method_timeout: Some(Duration::from_secs(5)),
+            ..Default::default()
+        };
+
+        let manager = ColorManager::builder(config.clone())
+            .connection(manager.inner().connection())
+            .build()
+            .await
+            .unwrap();
+        let device = manager.find_device_by_id("xrandr-Foo").await.unwrap();
+        assert_eq!(device.config(), &config);
+        assert_eq!(device.model().await.unwrap(), "");
+
+        let sibling = ColorManager::from_device(&device).await.unwrap();
+        assert_eq!(sibling.config(), &config);
+    });
+}
+
+#[test]
+fn refresh() {
+    zbus::block_on(async {
+        let (manager, server) = mock::manager().await;
+        mock::add_device(&server, "xrandr-Foo", &[("Model", "Old")]).await;
+        let config = ClientConfig {
+            cache_properties: true,
+            ..Default::default()
+        };
+        let manager = ColorManager::builder(config)
+            .connection(manager.inner().connection())
+            .build()
+            .await
+            .unwrap();
+        let device = manager.find_device_by_id("xrandr-Foo").await.unwrap();
+        assert_eq!(device.model().await.unwrap(), "Old");
+
+        // The mock does not emit `PropertiesChanged`, so the cache is stale.
+        device.set_property("Model", "New").await.unwrap();
+        assert_eq!(device.model().await.unwrap(), "Old");
+
+        let clone = device.clone();
+        device.refresh().await.unwrap();
+        assert_eq!(device.model().await.unwrap(), "New");
+        assert_eq!(clone.model().await.unwrap(), "New");
+    });
+}
+
+#[test]
+fn create_profile_with_fd_keeps_fd() {
+    zbus::block_on(async {
+        let (manager, server) = mock::manager().await;
+
+        let temp = mock::TempFile::new(b"profile contents");
+        let mut file = temp.open();
+
+        let properties = HashMap::from([("Filename".to_owned(), "test.icc".to_owned())]);
+        let profile = manager
+            .create_profile_with_fd("icc-test", Scope::Temp, &file, properties)
+            .await
+            .unwrap();
+        assert_eq!(
+            profile.inner().path().as_str(),
+            "/org/freedesktop/ColorManager/profiles/icc_test"
+        );
+        assert_eq!(
+            mock::profile_data(&server, "icc-test").await,
+            b"profile contents"
+        );
+
+        // The file descriptor of the caller is still usable.
+        let mut contents = String::new();
+        file.rewind().unwrap();
+        file.read_to_string(&mut contents).unwrap();
+        assert_eq!(contents, "profile contents");
+    });
+}
+
+#[test]
+fn create_profile_with_fd_temporary() {
+    zbus::block_on(async {
+        let (manager, server) = mock::manager().await;
+
+        let temp = mock::TempFile::new(b"temporary contents");
+
+        manager
+            .create_profile_with_fd(
+                "icc-temporary",
+                Scope::Temp,
+                temp.open(),
+                HashMap::<&str, &str>::new(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(
+            mock::profile_data(&server, "icc-temporary").await,
+            b"temporary contents"
+        );
+    });
+}
+
+#[test]
+fn create_profile_with_path_and_raw_fd() {
+    zbus::block_on(async {
+        let (manager, server) = mock::manager().await;
+        let temp = mock::TempFile::new(b"path contents");
+
+        manager
+            .create_profile_with_path("icc-path", Scope::Temp, temp.path(), [("Title", "Path")])
+            .await
+            .unwrap();
+        assert_eq!(
+            mock::profile_data(&server, "icc-path").await,
+            b"path contents"
+        );
+
+        let file = temp.open();
+        unsafe {
+            manager
+                .create_profile_with_raw_fd(
+                    "icc-raw",
+                    Scope::Temp,
+                    file.as_raw_fd(),
+                    HashMap::<&str, &str>::new(),
+                )
+                .await
+                .unwrap();
+        }
+        assert_eq!(
+            mock::profile_data(&server, "icc-raw").await,
+            b"path contents"
+        );
+    });
+}
+
+#[test]
+fn create_profile_from_bytes() {
+    zbus::block_on(async {
+        let (manager, server) = mock::manager().await;
+
+        manager
+            .create_profile_from_bytes("icc-memory", Scope::Temp, b"memory contents")
+            .await
+            .unwrap();
+        assert_eq!(
+            mock::profile_data(&server, "icc-memory").await,
+            b"memory contents"
+        );
+    });
+}
+
+/// A minimal ICC profile, only its header, differing by `tag`.
+fn icc_profile(tag: u8) -> Vec<u8> {
+    let mut data = vec![0; 128];
+    data[0..4].copy_from_slice(&128u32.to_be_bytes());
+    data[36..40].copy_from_slice(b"acsp");
+    data[127] = tag;
+    data
+}
+
+#[test]
+fn create_profile_from_file() {
+    zbus::block_on(async {
+        let (manager, server) = mock::manager().await;
+        let first = mock::TempFile::new(&icc_profile(1));
+        let second = mock::TempFile::new(&icc_profile(2));
+
+        let profile = manager
+            .create_profile_from_file(first.path(), Scope::Temp)
+            .await
+            .unwrap();
+        let profile_id = profile.profile_id().await.unwrap();
+        assert_eq!(profile_id, "icc-18f556ac93838d345bfaca5a3bc4edf0");
+        assert_eq!(
+            mock::profile_data(&server, &profile_id).await,
+            icc_profile(1)
+        );
+
+        // The ID depends on the contents, not on the file name.
+        let other = manager
+            .create_profile_from_file(second.path(), Scope::Temp)
+            .await
+            .unwrap();
+        assert_ne!(other.profile_id().await.unwrap(), profile_id);
+        assert!(manager
+            .create_profile_from_file(first.path(), Scope::Temp)
+            .await
+            .is_err());
+    });
+}
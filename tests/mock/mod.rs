@@ -0,0 +1,750 @@
+//! A minimal in-process implementation of the colord DBus API, served on a
+//! private peer to peer connection.
+
+use std::{
+    collections::HashMap,
+    fs::{self, File},
+    io::Read,
+    os::unix::{
+        io::{AsRawFd, BorrowedFd},
+        net::UnixStream,
+    },
+    path::{Path, PathBuf},
+    sync::atomic::{AtomicUsize, Ordering},
+};
+
+use color_manager::{device, ColorManager};
+use futures_util::future::try_join;
+use zbus::{
+    dbus_interface,
+    zvariant::{Fd, OwnedObjectPath},
+    Connection, ConnectionBuilder, DBusError, Guid, Interface, MessageHeader, ObjectServer,
+    SignalContext,
+};
+
+pub const MANAGER_PATH: &str = "/org/freedesktop/ColorManager";
+
+#[derive(Debug, DBusError)]
+#[dbus_error(prefix = "org.freedesktop.ColorManager")]
+pub enum MockError {
+    #[dbus_error(zbus_error)]
+    ZBus(zbus::Error),
+    NotFound(String),
+    #[dbus_error(name = "Device.ProfileDoesNotExist")]
+    ProfileDoesNotExist(String),
+    FailedToAuthenticate(String),
+    AlreadyExists(String),
+    #[dbus_error(name = "Sensor.NoSupport")]
+    NoSupport(String),
+}
+
+fn sensor_path(sensor_id: &str) -> OwnedObjectPath {
+    format!("{MANAGER_PATH}/sensors/{sensor_id}")
+        .try_into()
+        .unwrap()
+}
+
+fn profile_path(profile_id: &str) -> OwnedObjectPath {
+    let id = device::mangle_id(profile_id);
+    format!("{MANAGER_PATH}/profiles/{id}").try_into().unwrap()
+}
+
+fn device_path(device_id: &str) -> OwnedObjectPath {
+    let id = device::mangle_id(device_id);
+    format!("{MANAGER_PATH}/devices/{id}").try_into().unwrap()
+}
+
+/// The `org.freedesktop.ColorManager` interface, devices are added with
+/// [`add_device`].
+#[derive(Debug, Default)]
+pub struct MockColorManager {
+    devices: Vec<String>,
+    /// The `Kind` property of each device.
+    kinds: HashMap<String, String>,
+    profiles: Vec<String>,
+    /// The profile IDs by the basename of their `Filename` property.
+    filenames: HashMap<String, String>,
+    /// The IDs of the profiles that cannot be deleted.
+    system_wide: Vec<String>,
+    /// The data read from the file descriptor of each profile created with
+    /// `CreateProfileWithFd`.
+    profile_data: HashMap<String, Vec<u8>>,
+    sensors: Vec<String>,
+}
+
+#[dbus_interface(name = "org.freedesktop.ColorManager")]
+impl MockColorManager {
+    fn get_devices(&self) -> Vec<OwnedObjectPath> {
+        self.devices.iter().map(|id| device_path(id)).collect()
+    }
+
+    fn get_devices_by_kind(&self, kind: &str) -> Vec<OwnedObjectPath> {
+        self.devices
+            .iter()
+            .filter(|id| self.kinds.get(*id).map(String::as_str) == Some(kind))
+            .map(|id| device_path(id))
+            .collect()
+    }
+
+    fn find_device_by_id(&self, device_id: &str) -> Result<OwnedObjectPath, MockError> {
+        self.devices
+            .iter()
+            .find(|id| *id == device_id)
+            .map(|id| device_path(id))
+            .ok_or_else(|| MockError::NotFound(format!("device id '{device_id}' does not exist")))
+    }
+
+    fn get_profiles(&self) -> Vec<OwnedObjectPath> {
+        self.profiles.iter().map(|id| profile_path(id)).collect()
+    }
+
+    fn find_profile_by_id(&self, profile_id: &str) -> Result<OwnedObjectPath, MockError> {
+        self.profiles
+            .iter()
+            .find(|id| *id == profile_id)
+            .map(|id| profile_path(id))
+            .ok_or_else(|| MockError::NotFound(format!("profile id '{profile_id}' does not exist")))
+    }
+
+    fn find_profile_by_filename(&self, filename: &str) -> Result<OwnedObjectPath, MockError> {
+        let basename = filename.rsplit('/').next().unwrap_or(filename);
+        self.filenames
+            .get(basename)
+            .map(|id| profile_path(id))
+            .ok_or_else(|| {
+                MockError::NotFound(format!("profile filename '{filename}' does not exist"))
+            })
+    }
+
+    async fn create_device(
+        &mut self,
+        device_id: String,
+        _scope: String,
+        properties: HashMap<String, String>,
+        #[zbus(object_server)] server: &ObjectServer,
+    ) -> Result<OwnedObjectPath, MockError> {
+        let path = device_path(&device_id);
+        if let Some(kind) = properties.get("Kind") {
+            self.kinds.insert(device_id.clone(), kind.clone());
+        }
+        server
+            .at(&path, MockDevice::new(&device_id, properties))
+            .await?;
+        self.devices.push(device_id);
+
+        Ok(path)
+    }
+
+    async fn create_profile_with_fd(
+        &mut self,
+        profile_id: String,
+        _scope: String,
+        handle: Fd,
+        properties: HashMap<String, String>,
+        #[zbus(object_server)] server: &ObjectServer,
+    ) -> Result<OwnedObjectPath, MockError> {
+        if self.profiles.contains(&profile_id) {
+            return Err(MockError::AlreadyExists(format!(
+                "profile id '{profile_id}' already exists"
+            )));
+        }
+        // SAFETY: the descriptor stays open for the duration of the call.
+        let fd = unsafe { BorrowedFd::borrow_raw(handle.as_raw_fd()) };
+        let mut data = Vec::new();
+        File::from(fd.try_clone_to_owned().unwrap())
+            .read_to_end(&mut data)
+            .unwrap();
+
+        let path = profile_path(&profile_id);
+        let profile = MockProfile {
+            profile_id: profile_id.clone(),
+            properties,
+        };
+        server.at(&path, profile).await?;
+        self.profile_data.insert(profile_id.clone(), data);
+        self.profiles.push(profile_id);
+
+        Ok(path)
+    }
+
+    fn delete_device(&mut self, path: OwnedObjectPath) -> Result<(), MockError> {
+        let index = self
+            .devices
+            .iter()
+            .position(|id| device_path(id) == path)
+            .ok_or_else(|| MockError::NotFound(format!("device path '{path}' not found")))?;
+        self.devices.remove(index);
+
+        Ok(())
+    }
+
+    fn delete_profile(&mut self, path: OwnedObjectPath) -> Result<(), MockError> {
+        let index = self
+            .profiles
+            .iter()
+            .position(|id| profile_path(id) == path)
+            .ok_or_else(|| MockError::NotFound(format!("profile path '{path}' not found")))?;
+        if self.system_wide.contains(&self.profiles[index]) {
+            return Err(MockError::FailedToAuthenticate(
+                "not authorized to delete system-wide profiles".to_owned(),
+            ));
+        }
+        self.profiles.remove(index);
+
+        Ok(())
+    }
+
+    fn get_sensors(&self) -> Vec<OwnedObjectPath> {
+        self.sensors.iter().map(|id| sensor_path(id)).collect()
+    }
+
+    fn find_sensor_by_id(&self, sensor_id: &str) -> Result<OwnedObjectPath, MockError> {
+        self.sensors
+            .iter()
+            .find(|id| *id == sensor_id)
+            .map(|id| sensor_path(id))
+            .ok_or_else(|| MockError::NotFound(format!("sensor id '{sensor_id}' does not exist")))
+    }
+
+    #[dbus_interface(signal)]
+    async fn device_added(ctxt: &SignalContext<'_>, path: &OwnedObjectPath) -> zbus::Result<()>;
+
+    #[dbus_interface(signal)]
+    async fn changed(ctxt: &SignalContext<'_>) -> zbus::Result<()>;
+
+    #[dbus_interface(property)]
+    fn daemon_version(&self) -> &str {
+        "1.4.6"
+    }
+
+    #[dbus_interface(property)]
+    fn system_vendor(&self) -> &str {
+        "Acme Corp."
+    }
+
+    #[dbus_interface(property)]
+    fn system_model(&self) -> &str {
+        "Anvil 3000"
+    }
+}
+
+/// The `org.freedesktop.ColorManager` interface of a daemon predating the
+/// `SystemVendor` and `SystemModel` properties.
+#[derive(Debug, Default)]
+pub struct MockOldColorManager;
+
+#[dbus_interface(name = "org.freedesktop.ColorManager")]
+impl MockOldColorManager {
+    #[dbus_interface(property)]
+    fn daemon_version(&self) -> &str {
+        "0.1.20"
+    }
+}
+
+/// The bus name of the caller, there is none on a peer to peer connection.
+fn sender(header: &MessageHeader<'_>) -> String {
+    match header.sender() {
+        Ok(Some(sender)) => sender.to_string(),
+        _ => ":peer".to_owned(),
+    }
+}
+
+/// The `org.freedesktop.ColorManager.Device` interface.
+#[derive(Debug)]
+pub struct MockDevice {
+    device_id: String,
+    properties: HashMap<String, String>,
+    enabled: bool,
+    profiling_inhibitors: Vec<String>,
+    profiles: Vec<(OwnedObjectPath, String)>,
+}
+
+impl MockDevice {
+    pub fn new(device_id: &str, properties: HashMap<String, String>) -> Self {
+        Self {
+            device_id: device_id.to_owned(),
+            properties,
+            enabled: true,
+            profiling_inhibitors: Vec::new(),
+            profiles: Vec::new(),
+        }
+    }
+
+    fn get(&self, key: &str) -> String {
+        self.properties.get(key).cloned().unwrap_or_default()
+    }
+}
+
+#[dbus_interface(name = "org.freedesktop.ColorManager.Device")]
+impl MockDevice {
+    fn set_property(&mut self, property_name: String, property_value: String) {
+        self.properties.insert(property_name, property_value);
+    }
+
+    async fn set_enabled(
+        &mut self,
+        enabled: bool,
+        #[zbus(signal_context)] ctxt: SignalContext<'_>,
+    ) -> Result<(), MockError> {
+        // As the daemon, nothing is emitted if the state does not change.
+        if self.enabled == enabled {
+            return Ok(());
+        }
+        self.enabled = enabled;
+        Self::changed(&ctxt).await?;
+
+        Ok(())
+    }
+
+    async fn add_profile(
+        &mut self,
+        relation: String,
+        profile: OwnedObjectPath,
+        #[zbus(signal_context)] ctxt: SignalContext<'_>,
+    ) -> Result<(), MockError> {
+        self.profiles.push((profile, relation));
+        Self::changed(&ctxt).await?;
+
+        Ok(())
+    }
+
+    async fn remove_profile(
+        &mut self,
+        profile: OwnedObjectPath,
+        #[zbus(signal_context)] ctxt: SignalContext<'_>,
+    ) -> Result<(), MockError> {
+        let len = self.profiles.len();
+        self.profiles.retain(|(path, _)| *path != profile);
+        if self.profiles.len() == len {
+            return Err(MockError::NotFound(format!(
+                "{} not found",
+                profile.as_str()
+            )));
+        }
+        Self::changed(&ctxt).await?;
+
+        Ok(())
+    }
+
+    /// Matches every qualifier, returns the default profile.
+    fn get_profile_for_qualifiers(
+        &self,
+        _qualifiers: Vec<String>,
+    ) -> Result<OwnedObjectPath, MockError> {
+        self.profiles
+            .first()
+            .map(|(path, _)| path.clone())
+            .ok_or_else(|| MockError::NotFound("no profile found".to_owned()))
+    }
+
+    fn get_profile_relation(&self, profile: OwnedObjectPath) -> Result<String, MockError> {
+        self.profiles
+            .iter()
+            .find(|(path, _)| *path == profile)
+            .map(|(_, relation)| relation.clone())
+            .ok_or_else(|| MockError::ProfileDoesNotExist(profile.to_string()))
+    }
+
+    #[dbus_interface(property)]
+    fn profiles(&self) -> Vec<OwnedObjectPath> {
+        self.profiles.iter().map(|(path, _)| path.clone()).collect()
+    }
+
+    #[dbus_interface(signal)]
+    async fn changed(ctxt: &SignalContext<'_>) -> zbus::Result<()>;
+
+    fn profiling_inhibit(&mut self, #[zbus(header)] header: MessageHeader<'_>) {
+        let sender = sender(&header);
+        self.profiling_inhibitors.push(sender);
+    }
+
+    fn profiling_uninhibit(&mut self, #[zbus(header)] header: MessageHeader<'_>) {
+        let sender = sender(&header);
+        self.profiling_inhibitors
+            .retain(|inhibitor| *inhibitor != sender);
+    }
+
+    #[dbus_interface(property)]
+    fn profiling_inhibitors(&self) -> Vec<String> {
+        self.profiling_inhibitors.clone()
+    }
+
+    #[dbus_interface(property)]
+    fn device_id(&self) -> &str {
+        &self.device_id
+    }
+
+    #[dbus_interface(property)]
+    fn model(&self) -> String {
+        self.get("Model")
+    }
+
+    #[dbus_interface(property)]
+    fn vendor(&self) -> String {
+        self.get("Vendor")
+    }
+
+    #[dbus_interface(property)]
+    fn serial(&self) -> String {
+        self.get("Serial")
+    }
+
+    #[dbus_interface(property)]
+    fn kind(&self) -> String {
+        self.get("Kind")
+    }
+
+    #[dbus_interface(property)]
+    fn seat(&self) -> String {
+        self.get("Seat")
+    }
+
+    #[dbus_interface(property)]
+    fn owner(&self) -> u32 {
+        0
+    }
+
+    #[dbus_interface(property)]
+    fn enabled(&self) -> bool {
+        self.enabled
+    }
+
+    #[dbus_interface(property)]
+    fn metadata(&self) -> HashMap<String, String> {
+        self.properties
+            .get("XRANDR_name")
+            .map(|name| HashMap::from([("XRANDR_name".to_owned(), name.clone())]))
+            .unwrap_or_default()
+    }
+}
+
+/// The `org.freedesktop.ColorManager.Profile` interface.
+#[derive(Debug)]
+pub struct MockProfile {
+    profile_id: String,
+    properties: HashMap<String, String>,
+}
+
+impl MockProfile {
+    fn get(&self, key: &str) -> String {
+        self.properties.get(key).cloned().unwrap_or_default()
+    }
+}
+
+#[dbus_interface(name = "org.freedesktop.ColorManager.Profile")]
+impl MockProfile {
+    fn set_property(&mut self, property_name: String, property_value: String) {
+        self.properties.insert(property_name, property_value);
+    }
+
+    #[dbus_interface(property)]
+    fn profile_id(&self) -> &str {
+        &self.profile_id
+    }
+
+    #[dbus_interface(property)]
+    fn title(&self) -> String {
+        self.get("Title")
+    }
+
+    #[dbus_interface(property)]
+    fn kind(&self) -> String {
+        self.get("Kind")
+    }
+
+    #[dbus_interface(property)]
+    fn colorspace(&self) -> String {
+        self.get("Colorspace")
+    }
+
+    /// The `Warnings` property given as a comma separated list.
+    #[dbus_interface(property)]
+    fn warnings(&self) -> Vec<String> {
+        let warnings = self.get("Warnings");
+        warnings
+            .split(',')
+            .filter(|warning| !warning.is_empty())
+            .map(str::to_owned)
+            .collect()
+    }
+
+    #[dbus_interface(property)]
+    fn qualifier(&self) -> String {
+        self.get("Qualifier")
+    }
+
+    #[dbus_interface(property)]
+    fn filename(&self) -> String {
+        self.get("Filename")
+    }
+
+    #[dbus_interface(property)]
+    fn has_vcgt(&self) -> bool {
+        false
+    }
+
+    #[dbus_interface(property)]
+    fn is_system_wide(&self) -> bool {
+        self.get("IsSystemWide") == "true"
+    }
+}
+
+/// The `org.freedesktop.ColorManager.Sensor` interface.
+#[derive(Debug, Default)]
+pub struct MockSensor {
+    locked: bool,
+    native: bool,
+    capabilities: Vec<String>,
+}
+
+#[dbus_interface(name = "org.freedesktop.ColorManager.Sensor")]
+impl MockSensor {
+    async fn lock(
+        &mut self,
+        #[zbus(signal_context)] ctxt: SignalContext<'_>,
+    ) -> zbus::fdo::Result<()> {
+        self.locked = true;
+        self.state_changed(&ctxt).await?;
+
+        Ok(())
+    }
+
+    async fn unlock(
+        &mut self,
+        #[zbus(signal_context)] ctxt: SignalContext<'_>,
+    ) -> zbus::fdo::Result<()> {
+        self.locked = false;
+        self.state_changed(&ctxt).await?;
+
+        Ok(())
+    }
+
+    fn get_sample(&self, capability: String) -> Result<(f64, f64, f64), MockError> {
+        if !self.capabilities.contains(&capability) {
+            return Err(MockError::NoSupport(format!("{capability} not supported")));
+        }
+
+        Ok((0.5, 0.5, 0.5))
+    }
+
+    #[dbus_interface(property)]
+    fn locked(&self) -> bool {
+        self.locked
+    }
+
+    #[dbus_interface(property)]
+    fn capabilities(&self) -> Vec<String> {
+        self.capabilities.clone()
+    }
+
+    #[dbus_interface(property)]
+    fn model(&self) -> &str {
+        "Dummy Sensor"
+    }
+
+    #[dbus_interface(property)]
+    fn native(&self) -> bool {
+        self.native
+    }
+
+    #[dbus_interface(property)]
+    fn vendor(&self) -> &str {
+        "Acme Corp."
+    }
+
+    #[dbus_interface(property)]
+    fn state(&self) -> &str {
+        if self.locked {
+            "busy"
+        } else {
+            "idle"
+        }
+    }
+}
+
+/// Serves a [`MockColorManager`] and returns a ColorManager connected to it,
+/// the server connection has to be kept alive for the duration of the test.
+pub async fn manager() -> (ColorManager<'static>, Connection) {
+    serve(MockColorManager::default()).await
+}
+
+/// Like [`manager`] but serves a [`MockOldColorManager`].
+pub async fn old_manager() -> (ColorManager<'static>, Connection) {
+    serve(MockOldColorManager).await
+}
+
+async fn serve<I: Interface>(manager: I) -> (ColorManager<'static>, Connection) {
+    let guid = Guid::generate();
+    let (p0, p1) = UnixStream::pair().unwrap();
+    let server = ConnectionBuilder::unix_stream(p0)
+        .server(&guid)
+        .p2p()
+        .serve_at(MANAGER_PATH, manager)
+        .unwrap()
+        .build();
+    let client = ConnectionBuilder::unix_stream(p1).p2p().build();
+    let (client, server) = try_join(client, server).await.unwrap();
+
+    (
+        ColorManager::from_connection(&client).await.unwrap(),
+        server,
+    )
+}
+
+/// Adds a device to the mock served by `server` and emits `DeviceAdded`.
+pub async fn add_device(
+    server: &Connection,
+    device_id: &str,
+    properties: &[(&str, &str)],
+) -> OwnedObjectPath {
+    let path = device_path(device_id);
+    let kind = properties
+        .iter()
+        .find(|(key, _)| *key == "Kind")
+        .map(|(_, kind)| kind.to_string());
+    let properties = properties
+        .iter()
+        .map(|(key, value)| (key.to_string(), value.to_string()))
+        .collect();
+    let object_server = server.object_server();
+    object_server
+        .at(&path, MockDevice::new(device_id, properties))
+        .await
+        .unwrap();
+
+    let manager = object_server
+        .interface::<_, MockColorManager>(MANAGER_PATH)
+        .await
+        .unwrap();
+    {
+        let mut manager = manager.get_mut().await;
+        manager.devices.push(device_id.to_owned());
+        if let Some(kind) = kind {
+            manager.kinds.insert(device_id.to_owned(), kind);
+        }
+    }
+    MockColorManager::device_added(manager.signal_context(), &path)
+        .await
+        .unwrap();
+    MockColorManager::changed(manager.signal_context())
+        .await
+        .unwrap();
+
+    path
+}
+
+/// Adds a sensor with a native driver to the mock served by `server`.
+pub async fn add_sensor(server: &Connection, sensor_id: &str) -> OwnedObjectPath {
+    add_sensor_with_driver(server, sensor_id, true).await
+}
+
+/// Adds a sensor to the mock served by `server`, `native` if it does not need
+/// argyllcms. Only native sensors can be calibrated.
+pub async fn add_sensor_with_driver(
+    server: &Connection,
+    sensor_id: &str,
+    native: bool,
+) -> OwnedObjectPath {
+    let path = sensor_path(sensor_id);
+    let mut capabilities = vec!["lcd".to_owned()];
+    if native {
+        capabilities.push("calibration".to_owned());
+    }
+    let sensor = MockSensor {
+        native,
+        capabilities,
+        ..Default::default()
+    };
+    let object_server = server.object_server();
+    object_server.at(&path, sensor).await.unwrap();
+
+    let manager = object_server
+        .interface::<_, MockColorManager>(MANAGER_PATH)
+        .await
+        .unwrap();
+    manager.get_mut().await.sensors.push(sensor_id.to_owned());
+
+    path
+}
+
+/// The data the mock served by `server` read for the profile `profile_id`
+/// created with `CreateProfileWithFd`.
+pub async fn profile_data(server: &Connection, profile_id: &str) -> Vec<u8> {
+    let manager = server
+        .object_server()
+        .interface::<_, MockColorManager>(MANAGER_PATH)
+        .await
+        .unwrap();
+    let manager = manager.get().await;
+
+    manager.profile_data[profile_id].clone()
+}
+
+/// Adds a profile to the mock served by `server`.
+pub async fn add_profile(
+    server: &Connection,
+    profile_id: &str,
+    properties: &[(&str, &str)],
+) -> OwnedObjectPath {
+    let path = profile_path(profile_id);
+    let profile = MockProfile {
+        profile_id: profile_id.to_owned(),
+        properties: properties
+            .iter()
+            .map(|(key, value)| (key.to_string(), value.to_string()))
+            .collect(),
+    };
+    let object_server = server.object_server();
+    object_server.at(&path, profile).await.unwrap();
+
+    let manager = object_server
+        .interface::<_, MockColorManager>(MANAGER_PATH)
+        .await
+        .unwrap();
+    let mut manager = manager.get_mut().await;
+    manager.profiles.push(profile_id.to_owned());
+    if properties.contains(&("IsSystemWide", "true")) {
+        manager.system_wide.push(profile_id.to_owned());
+    }
+    if let Some((_, filename)) = properties.iter().find(|(key, _)| *key == "Filename") {
+        let basename = filename.rsplit('/').next().unwrap_or(filename);
+        manager
+            .filenames
+            .insert(basename.to_owned(), profile_id.to_owned());
+    }
+
+    path
+}
+
+/// A file in the temporary directory, named uniquely so that concurrent test
+/// runs do not share it, and removed when dropped.
+pub struct TempFile(PathBuf);
+
+impl TempFile {
+    pub fn new(contents: &[u8]) -> Self {
+        static COUNT: AtomicUsize = AtomicUsize::new(0);
+        let name = format!(
+            "color-manager-{}-{}.icc",
+            std::process::id(),
+            COUNT.fetch_add(1, Ordering::Relaxed)
+        );
+        let path = std::env::temp_dir().join(name);
+        fs::write(&path, contents).unwrap();
+
+        Self(path)
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.0
+    }
+
+    pub fn open(&self) -> File {
+        File::open(&self.0).unwrap()
+    }
+}
+
+impl Drop for TempFile {
+    fn drop(&mut self) {
+        let _ = fs::remove_file(&self.0);
+    }
+}